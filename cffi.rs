@@ -0,0 +1,151 @@
+//! C interface for embedding in firmware
+//!
+//! Enabled by the `cffi` feature; build a linkable library with
+//! `cargo rustc --release --features cffi --crate-type staticlib`. The
+//! matching header is `ilc.h`, generated by cbindgen from this module
+//! (`cbindgen --config cbindgen.toml --output ilc.h`). Every function
+//! returns `ILC_OK` or one of the negative `ILC_ERR_*` codes; sketches
+//! use the `to_bytes` wire format.
+
+use crate::ring::{N, Q};
+use crate::types::ILCError;
+use std::slice;
+
+pub const ILC_OK: i32 = 0;
+pub const ILC_ERR_RECONSTRUCTION_FAILED: i32 = -1;
+pub const ILC_ERR_CHECKSUM_MISMATCH: i32 = -2;
+pub const ILC_ERR_INVALID_INPUT: i32 = -3;
+pub const ILC_ERR_UNKNOWN_SEED_ID: i32 = -4;
+pub const ILC_ERR_BAD_MAGIC: i32 = -5;
+pub const ILC_ERR_SERIALIZATION: i32 = -6;
+pub const ILC_ERR_DESERIALIZATION: i32 = -7;
+pub const ILC_ERR_UNSUPPORTED_VERSION: i32 = -8;
+pub const ILC_ERR_OVERFLOW: i32 = -9;
+/// A required pointer was null
+pub const ILC_ERR_NULL_POINTER: i32 = -10;
+/// The output buffer is too small; the needed size is stored in `out_len`
+pub const ILC_ERR_BUFFER_TOO_SMALL: i32 = -11;
+/// The sketch was written by a build with different layout flags
+pub const ILC_ERR_UNSUPPORTED_FLAGS: i32 = -12;
+
+fn error_code(err: &ILCError) -> i32 {
+    match err {
+        ILCError::ReconstructionFailed => ILC_ERR_RECONSTRUCTION_FAILED,
+        ILCError::ChecksumMismatch => ILC_ERR_CHECKSUM_MISMATCH,
+        ILCError::InvalidInput => ILC_ERR_INVALID_INPUT,
+        ILCError::UnknownSeedId(_) => ILC_ERR_UNKNOWN_SEED_ID,
+        ILCError::BadMagic => ILC_ERR_BAD_MAGIC,
+        ILCError::Serialization(_) => ILC_ERR_SERIALIZATION,
+        ILCError::Deserialization(_) => ILC_ERR_DESERIALIZATION,
+        ILCError::UnsupportedVersion(_) => ILC_ERR_UNSUPPORTED_VERSION,
+        ILCError::Overflow => ILC_ERR_OVERFLOW,
+        ILCError::UnsupportedFlags(_) => ILC_ERR_UNSUPPORTED_FLAGS,
+    }
+}
+
+/// Compress the N coefficients at `b` (each < q) under the 32-byte
+/// `seed` into `out`. On entry `*out_len` is the capacity of `out`; on
+/// success it is set to the bytes written. With a buffer that is too
+/// small, `*out_len` is set to the size needed and nothing is written;
+/// `out` may be null when `*out_len` is 0, to query the size.
+///
+/// # Safety
+///
+/// `b` must point to N readable `u16`s, `seed` to 32 readable bytes,
+/// `out_len` to a writable `usize`, and `out` to `*out_len` writable bytes
+/// (or be null if `*out_len` is 0).
+#[no_mangle]
+pub unsafe extern "C" fn ilc_compress(
+    b: *const u16,
+    seed: *const u8,
+    out: *mut u8,
+    out_len: *mut usize,
+) -> i32 {
+    if b.is_null() || seed.is_null() || out_len.is_null() || (out.is_null() && *out_len != 0) {
+        return ILC_ERR_NULL_POINTER;
+    }
+    let b: &[u16; N] = &*(b as *const [u16; N]);
+    if b.iter().any(|&c| c as u32 >= Q) {
+        return ILC_ERR_INVALID_INPUT;
+    }
+    let seed: [u8; 32] = *(seed as *const [u8; 32]);
+
+    let bytes = match crate::to_bytes(&crate::compress(b, seed)) {
+        Ok(bytes) => bytes,
+        Err(e) => return error_code(&e),
+    };
+    if bytes.len() > *out_len {
+        *out_len = bytes.len();
+        return ILC_ERR_BUFFER_TOO_SMALL;
+    }
+    slice::from_raw_parts_mut(out, bytes.len()).copy_from_slice(&bytes);
+    *out_len = bytes.len();
+    ILC_OK
+}
+
+/// Decode the `in_len`-byte sketch at `input` and write the N recovered
+/// coefficients of `b` to `out`
+///
+/// # Safety
+///
+/// `input` must point to `in_len` readable bytes and `out` to N writable
+/// `u16`s.
+#[no_mangle]
+pub unsafe extern "C" fn ilc_decompress(input: *const u8, in_len: usize, out: *mut u16) -> i32 {
+    if input.is_null() || out.is_null() {
+        return ILC_ERR_NULL_POINTER;
+    }
+    let bytes = slice::from_raw_parts(input, in_len);
+    match crate::from_bytes(bytes).and_then(|sketch| crate::decompress(&sketch)) {
+        Ok(coeffs) => {
+            slice::from_raw_parts_mut(out, N).copy_from_slice(&coeffs);
+            ILC_OK
+        }
+        Err(e) => error_code(&e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ring::RingElement;
+    use std::ptr;
+
+    #[test]
+    fn test_c_roundtrip() {
+        let seed = [9u8; 32];
+        let b = RingElement::from_seed(&seed, 1).coeffs;
+        let wire_len = crate::to_bytes(&crate::compress(&b, seed)).unwrap().len();
+
+        // Size query with a null buffer, then the real call
+        let mut out_len = 0;
+        let rc = unsafe { ilc_compress(b.as_ptr(), seed.as_ptr(), ptr::null_mut(), &mut out_len) };
+        assert_eq!(rc, ILC_ERR_BUFFER_TOO_SMALL);
+        assert_eq!(out_len, wire_len);
+
+        // A null buffer claiming capacity is refused
+        let mut claimed = 16;
+        let rc = unsafe { ilc_compress(b.as_ptr(), seed.as_ptr(), ptr::null_mut(), &mut claimed) };
+        assert_eq!(rc, ILC_ERR_NULL_POINTER);
+
+        let mut out = vec![0u8; out_len + 16];
+        out_len = out.len();
+        let rc = unsafe { ilc_compress(b.as_ptr(), seed.as_ptr(), out.as_mut_ptr(), &mut out_len) };
+        assert_eq!(rc, ILC_OK);
+        assert_eq!(out_len, wire_len);
+
+        let mut recovered = [0u16; N];
+        let rc = unsafe { ilc_decompress(out.as_ptr(), out_len, recovered.as_mut_ptr()) };
+        assert_eq!(rc, ILC_OK);
+        assert_eq!(recovered, b);
+
+        // Truncated input, a flipped bit, null pointers
+        let rc = unsafe { ilc_decompress(out.as_ptr(), out_len - 1, recovered.as_mut_ptr()) };
+        assert!(rc < 0);
+        out[out_len / 2] ^= 1;
+        let rc = unsafe { ilc_decompress(out.as_ptr(), out_len, recovered.as_mut_ptr()) };
+        assert!(rc < 0);
+        let rc = unsafe { ilc_decompress(ptr::null(), 0, recovered.as_mut_ptr()) };
+        assert_eq!(rc, ILC_ERR_NULL_POINTER);
+    }
+}
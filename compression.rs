@@ -1,7 +1,7 @@
 //! Benchmarks for ILC compression
 
 use criterion::{criterion_group, criterion_main, Criterion, black_box};
-use ilc_rs::{RingElement, RingLWEKey, AlgebraicShield, N};
+use ilc_rs::{RingElement, RingLWEKey, AlgebraicShield, ChecksumKind, N, Q};
 use rand::Rng;
 
 fn create_test_key() -> (RingLWEKey, [u8; 32]) {
@@ -21,6 +21,22 @@ fn bench_compression(c: &mut Criterion) {
     });
 }
 
+/// Compress and decompress under each checksum algorithm; the gap to
+/// `None` is the checksum's cost on each side
+fn bench_checksum_kinds(c: &mut Criterion) {
+    let (key, seed) = create_test_key();
+    
+    for (name, kind) in [("sha3", ChecksumKind::Sha3), ("crc32", ChecksumKind::Crc32), ("none", ChecksumKind::None)] {
+        c.bench_function(&format!("compress_checksum_{}", name), |b| {
+            b.iter(|| black_box(key.compress_with_checksum(seed, kind).unwrap()))
+        });
+        let compressed = key.compress_with_checksum(seed, kind).unwrap();
+        c.bench_function(&format!("decompress_checksum_{}", name), |b| {
+            b.iter(|| black_box(RingLWEKey::decompress(&compressed).unwrap()))
+        });
+    }
+}
+
 fn bench_decompression(c: &mut Criterion) {
     let (key, seed) = create_test_key();
     let compressed = key.compress(seed);
@@ -32,6 +48,31 @@ fn bench_decompression(c: &mut Criterion) {
     });
 }
 
+/// Same as `decompress`, with anchors and parity interleaved in one vector
+fn bench_decompression_interleaved(c: &mut Criterion) {
+    let (key, seed) = create_test_key();
+    let interleaved = key.compress(seed).to_interleaved().unwrap();
+    
+    c.bench_function("decompress_interleaved", |b| {
+        b.iter(|| {
+            black_box(RingLWEKey::decompress_interleaved(&interleaved).unwrap())
+        })
+    });
+}
+
+/// Schoolbook `mul` against the NTT-based `mul_ntt`
+fn bench_multiplication(c: &mut Criterion) {
+    let (key, seed) = create_test_key();
+    let s = RingElement::from_seed(&seed, 2);
+    
+    c.bench_function("mul_schoolbook", |b| {
+        b.iter(|| black_box(key.a.mul(&s)))
+    });
+    c.bench_function("mul_ntt", |b| {
+        b.iter(|| black_box(key.a.mul_ntt(&s)))
+    });
+}
+
 fn bench_roundtrip(c: &mut Criterion) {
     let (key, seed) = create_test_key();
     
@@ -43,17 +84,31 @@ fn bench_roundtrip(c: &mut Criterion) {
     });
 }
 
+/// End-to-end cost over the wire: compress -> bytes -> sketch -> key
+fn bench_wire_roundtrip(c: &mut Criterion) {
+    let (key, seed) = create_test_key();
+    
+    c.bench_function("wire_roundtrip", |b| {
+        b.iter(|| {
+            let compressed = key.compress(seed);
+            let bytes = ilc_rs::to_bytes(&compressed).unwrap();
+            let sketch = ilc_rs::from_bytes(&bytes).unwrap();
+            black_box(RingLWEKey::decompress(&sketch).unwrap())
+        })
+    });
+}
+
 fn bench_serialization(c: &mut Criterion) {
     let (key, seed) = create_test_key();
     let compressed = key.compress(seed);
     
     c.bench_function("serialize", |b| {
         b.iter(|| {
-            black_box(ilc_rs::to_bytes(&compressed))
+            black_box(ilc_rs::to_bytes(&compressed).unwrap())
         })
     });
     
-    let bytes = ilc_rs::to_bytes(&compressed);
+    let bytes = ilc_rs::to_bytes(&compressed).unwrap();
     c.bench_function("deserialize", |b| {
         b.iter(|| {
             black_box(ilc_rs::from_bytes(&bytes).unwrap())
@@ -67,7 +122,7 @@ fn bench_bandwidth_simulation(c: &mut Criterion) {
     let compressed = key.compress(seed);
     
     let original_bytes = key.size_bytes();
-    let compressed_bytes = ilc_rs::to_bytes(&compressed).len();
+    let compressed_bytes = ilc_rs::to_bytes(&compressed).unwrap().len();
     
     // 100 kbps = 12,500 bytes/sec
     let bps = 12_500.0;
@@ -89,12 +144,71 @@ fn bench_bandwidth_simulation(c: &mut Criterion) {
     });
 }
 
+/// Centered binomial sample with parameter eta, mapped into [0, q)
+fn cbd_error(eta: u32) -> RingElement {
+    let mut rng = rand::thread_rng();
+    let mut e = RingElement::default();
+    for c in &mut e.coeffs {
+        let x: u32 = (0..eta).map(|_| rng.gen_range(0..2u32)).sum();
+        let y: u32 = (0..eta).map(|_| rng.gen_range(0..2u32)).sum();
+        *c = ((x + Q - y) % Q) as u16;
+    }
+    e
+}
+
+/// `weight` coefficients of +/-1, the rest zero
+fn sparse_error(weight: usize) -> RingElement {
+    let mut rng = rand::thread_rng();
+    let mut e = RingElement::default();
+    for _ in 0..weight {
+        let i = rng.gen_range(0..N);
+        e.coeffs[i] = if rng.gen::<bool>() { 1 } else { (Q - 1) as u16 };
+    }
+    e
+}
+
+/// Compression ratio for keys b = a*s + e under different error
+/// distributions. The ratio is the same for all of them: the savings come
+/// from regenerating 'a' from the seed, not from any structure in 'b'.
+fn bench_error_distributions(c: &mut Criterion) {
+    let seed = rand::thread_rng().gen::<[u8; 32]>();
+    let a = RingElement::from_seed(&seed, 0);
+    let s = RingElement::from_seed(&seed, 1);
+    
+    let errors = [
+        ("uniform", RingElement::from_seed(&seed, 2)),
+        ("cbd_eta2", cbd_error(2)),
+        ("sparse_16", sparse_error(16)),
+    ];
+    
+    println!("\n=== Compression ratio by error distribution ===");
+    for (name, e) in &errors {
+        let key = RingLWEKey { a: a.clone(), b: a.mul_add(&s, e) };
+        let compressed = key.compress(seed);
+        let wire_bytes = ilc_rs::to_bytes(&compressed).unwrap().len();
+        println!("{:<10} {} / {} bytes ({:.1}%)",
+                 name, wire_bytes, key.size_bytes(),
+                 wire_bytes as f64 / key.size_bytes() as f64 * 100.0);
+        
+        c.bench_function(&format!("compress_{}", name), |b| {
+            b.iter(|| {
+                black_box(key.compress(seed))
+            })
+        });
+    }
+}
+
 criterion_group!(
     benches,
     bench_compression,
+    bench_checksum_kinds,
     bench_decompression,
+    bench_decompression_interleaved,
+    bench_multiplication,
     bench_roundtrip,
+    bench_wire_roundtrip,
     bench_serialization,
     bench_bandwidth_simulation,
+    bench_error_distributions,
 );
 criterion_main!(benches);
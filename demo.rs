@@ -34,7 +34,7 @@ fn main() {
     println!("Compressed size:          {} bytes", compressed_size);
     
     // Serialized wire format
-    let wire_bytes = ilc_rs::to_bytes(&compressed);
+    let wire_bytes = ilc_rs::to_bytes(&compressed).expect("compress output always serializes");
     println!("Wire format size:         {} bytes", wire_bytes.len());
     
     // Compression stats
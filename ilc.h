@@ -0,0 +1,70 @@
+#ifndef ILC_H
+#define ILC_H
+
+/* Generated by cbindgen from cffi.rs; do not edit by hand. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define ILC_OK 0
+
+#define ILC_ERR_RECONSTRUCTION_FAILED -1
+
+#define ILC_ERR_CHECKSUM_MISMATCH -2
+
+#define ILC_ERR_INVALID_INPUT -3
+
+#define ILC_ERR_UNKNOWN_SEED_ID -4
+
+#define ILC_ERR_BAD_MAGIC -5
+
+#define ILC_ERR_SERIALIZATION -6
+
+#define ILC_ERR_DESERIALIZATION -7
+
+#define ILC_ERR_UNSUPPORTED_VERSION -8
+
+#define ILC_ERR_OVERFLOW -9
+
+/**
+ * A required pointer was null
+ */
+#define ILC_ERR_NULL_POINTER -10
+
+/**
+ * The output buffer is too small; the needed size is stored in `out_len`
+ */
+#define ILC_ERR_BUFFER_TOO_SMALL -11
+
+/**
+ * The sketch was written by a build with different layout flags
+ */
+#define ILC_ERR_UNSUPPORTED_FLAGS -12
+
+/**
+ * Compress the N coefficients at `b` (each < q) under the 32-byte
+ * `seed` into `out`. On entry `*out_len` is the capacity of `out`; on
+ * success it is set to the bytes written. With a buffer that is too
+ * small, `*out_len` is set to the size needed and nothing is written;
+ * `out` may be null when `*out_len` is 0, to query the size.
+ *
+ * # Safety
+ *
+ * `b` must point to N readable `u16`s, `seed` to 32 readable bytes,
+ * `out_len` to a writable `usize`, and `out` to `*out_len` writable bytes
+ * (or be null if `*out_len` is 0).
+ */
+int32_t ilc_compress(const uint16_t *b, const uint8_t *seed, uint8_t *out, size_t *out_len);
+
+/**
+ * Decode the `in_len`-byte sketch at `input` and write the N recovered
+ * coefficients of `b` to `out`
+ *
+ * # Safety
+ *
+ * `input` must point to `in_len` readable bytes and `out` to N writable
+ * `u16`s.
+ */
+int32_t ilc_decompress(const uint8_t *input, size_t in_len, uint16_t *out);
+
+#endif  /* ILC_H */
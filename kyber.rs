@@ -0,0 +1,186 @@
+//! Kyber public key encoding
+//!
+//! A Kyber public key is `ByteEncode12(t_0) || ... || ByteEncode12(t_{k-1})
+//! || rho`: each polynomial packed at 12 bits per coefficient (384 bytes),
+//! followed by the 32-byte seed rho. Kyber-512 has k = 2.
+//! The polynomials are encoded as stored; Kyber keeps `t` in the NTT
+//! domain, so a key parsed here round-trips byte for byte.
+//!
+//! Only the encoding is shared with Kyber. rho is kept as `a_seed`, but
+//! `ModuleLWEKey::matrix_a` expands it with this crate's `from_seed`, not
+//! Kyber's SHAKE128 `SampleNTT`, so it does not yield the `A` a Kyber
+//! implementation derives from the same key.
+
+use crate::ring::{RingElement, N, Q};
+use crate::types::{AlgebraicShield, CompressedPK, ILCError, ModuleLWEKey};
+
+/// Bytes per 12-bit packed polynomial
+pub const POLY_BYTES: usize = N * 12 / 8;
+
+/// Encoded size of a Kyber-512 public key
+pub const KYBER512_PK_BYTES: usize = 2 * POLY_BYTES + 32;
+
+/// Pack reduced coefficients two per three bytes, little-endian
+fn encode12(poly: &RingElement, out: &mut Vec<u8>) {
+    for pair in poly.coeffs.chunks(2) {
+        let (x, y) = (pair[0], pair[1]);
+        out.extend([x as u8, (x >> 8) as u8 | (y << 4) as u8, (y >> 4) as u8]);
+    }
+}
+
+/// Inverse of `encode12`, rejecting coefficients >= q as FIPS 203 requires
+fn decode12(bytes: &[u8]) -> Result<RingElement, ILCError> {
+    let mut poly = RingElement::default();
+    for (pair, b) in poly.coeffs.chunks_mut(2).zip(bytes.chunks(3)) {
+        pair[0] = b[0] as u16 | (b[1] as u16 & 0x0f) << 8;
+        pair[1] = (b[1] >> 4) as u16 | (b[2] as u16) << 4;
+    }
+    if poly.coeffs.iter().any(|&c| c as u32 >= Q) {
+        return Err(ILCError::InvalidInput);
+    }
+    Ok(poly)
+}
+
+impl ModuleLWEKey {
+    /// Standard Kyber encoding of the key, `POLY_BYTES` per component of
+    /// `t` followed by `a_seed`
+    pub fn to_kyber_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.rank() * POLY_BYTES + 32);
+        for poly in &self.t {
+            encode12(poly, &mut out);
+        }
+        out.extend_from_slice(&self.a_seed);
+        out
+    }
+
+    /// Kyber-512 encoding of the key, the inverse of `from_kyber512_pk`.
+    /// Fails with `InvalidInput` unless the key has rank 2.
+    pub fn to_kyber512_pk(&self) -> Result<Vec<u8>, ILCError> {
+        if self.rank() != 2 {
+            return Err(ILCError::InvalidInput);
+        }
+        Ok(self.to_kyber_bytes())
+    }
+}
+
+/// Parse a Kyber public key of any rank from 1 to `ModuleLWEKey::MAX_RANK`.
+/// Fails with `InvalidInput` on a length that isn't `k * POLY_BYTES + 32`
+/// for such a k, or a coefficient >= q.
+pub fn from_kyber_pk(bytes: &[u8]) -> Result<ModuleLWEKey, ILCError> {
+    let body_len = bytes.len().checked_sub(32).ok_or(ILCError::InvalidInput)?;
+    if body_len == 0 || body_len % POLY_BYTES != 0 || body_len / POLY_BYTES > ModuleLWEKey::MAX_RANK {
+        return Err(ILCError::InvalidInput);
+    }
+    let (body, seed) = bytes.split_at(body_len);
+    Ok(ModuleLWEKey {
+        a_seed: seed.try_into().map_err(|_| ILCError::InvalidInput)?,
+        t: body.chunks(POLY_BYTES).map(decode12).collect::<Result<_, _>>()?,
+    })
+}
+
+/// Parse a Kyber-512 public key (`KYBER512_PK_BYTES` long)
+pub fn from_kyber512_pk(bytes: &[u8]) -> Result<ModuleLWEKey, ILCError> {
+    if bytes.len() != KYBER512_PK_BYTES {
+        return Err(ILCError::InvalidInput);
+    }
+    from_kyber_pk(bytes)
+}
+
+impl CompressedPK {
+    /// Decompress a module sketch and emit the standard Kyber public key
+    /// encoding in one step, with the resolved seed as rho
+    pub fn to_kyber_pk(&self) -> Result<Vec<u8>, ILCError> {
+        Ok(ModuleLWEKey::decompress(self)?.to_kyber_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kyber512_pk_roundtrip() {
+        let seed = [21u8; 32];
+        let key = ModuleLWEKey {
+            a_seed: seed,
+            t: vec![RingElement::from_seed(&seed, 0xf0), RingElement::from_seed(&seed, 0xf1)],
+        };
+        
+        let pk = key.compress(seed).to_kyber_pk().unwrap();
+        assert_eq!(pk.len(), KYBER512_PK_BYTES);
+        assert_eq!(pk, key.to_kyber_bytes());
+        
+        let parsed = from_kyber512_pk(&pk).unwrap();
+        assert_eq!(parsed.t, key.t);
+        assert_eq!(parsed.a_seed, seed);
+        
+        // Wrong length, and a coefficient of q
+        assert!(from_kyber512_pk(&pk[1..]).is_err());
+        let mut bad = pk.clone();
+        bad[0] = (Q & 0xff) as u8;
+        bad[1] = (bad[1] & 0xf0) | (Q >> 8) as u8;
+        assert!(matches!(from_kyber512_pk(&bad), Err(ILCError::InvalidInput)));
+    }
+
+    /// ML-KEM-512 encapsulation key from FIPS 203 `KeyGen_internal` with
+    /// d = 00 01 .. 1f (z doesn't enter the public key)
+    const ML_KEM_512_PK: &str = concat!(
+        "3995815e597d104355cf29aa5333c93251869d5bcdbe487124f602b8b6a66c16",
+        "c4761648ad765cf5d8006b515e905a7f0ac076b0c62efa328153e7ca5701699f",
+        "1305f1e6bc6f90b0e49b693512b6ce992a8b8016ddfc1a662c7e3f9619cbd869",
+        "dd771af30896ccd5918ac6cb77466c5e779996d67ff9aabc97503f2c7b7e2d00",
+        "0d86450fb1807ca4cabda465825a31c789a1b7a491ab3872765d320d0b71920f",
+        "a213c94093416b83b8124e69f65e62cb5000dcc37aa9a0fff73970c4772f357d",
+        "24189ca6f5305568c0e2376a3762a68c605e563c5d209572e0fc7532ca294729",
+        "535567b5fc413c5e8792d2464536cc808f98add74664f141566f9016a90a5418",
+        "29a98a0464ce41a8bb44c2d4fa3c2c209460728ef14a1a7c4c9b98d12203b4cc",
+        "3529160a9ab2d7838f7ff6b53ae05aa31a7d646b7afa6c45932526a3c3755619",
+        "be994c211c2a31c05b3447836cb2150be1829dae6b04c5535cff546e392ba797",
+        "411720f924f490a5ac5495f21356d550b782a64c1688b6b655bcc7842197a434",
+        "c2f6563b5b7f09a78bcc488232783561d16f4cbab6755400050781570c66604b",
+        "817ad1252294736e8b01861a4b5a74519b8b6fe51489a5072392e587626c7137",
+        "76575d33806a1c8e2732af97c2680f51666331c4eb8bbc0431c4f96832daf1b3",
+        "c45528fba153f6c78b1c198702947ccd337727a46fb53ba11de5cb4191346859",
+        "516cb6ad72400f3cf209b236aef35a580ac87eb3e30fafd66973ca8a7dd2675a",
+        "f41f7a17b61433cd1af80f7708869f665488497980b1ac10a0cdcb636a00ed86",
+        "81b35e429124ca80350725b85f83a5eac3a4a3cc1600903e65293560b9b336e5",
+        "af0d529dac1a048119302cb7a9bcc110b94851bf02117f199dc485a852b7473f",
+        "09b831a6831d5b54c0b790d225cf6bb92d9462a26cdb33dda5123c7aaf0e26a0",
+        "b83655eea28bf3a8074725018fd6bae4b601cf61baab71a7a3d35197a343e74b",
+        "4a272c125d540896426d85b7958d3b38a6ba987ec37225c7b44cdb12dde4539b",
+        "4ab082363683f04bf7a09cc5c41dfe830a1b162e0b324334362f084a14467723",
+        "344badd000f8d8c537c48f998f05307cebd1ede0b81c3bc59a065a1b6d63b26c",
+    );
+
+    fn hex_decode(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_kyber512_known_vector() {
+        let pk = hex_decode(ML_KEM_512_PK);
+        assert_eq!(pk.len(), KYBER512_PK_BYTES);
+
+        let key = from_kyber512_pk(&pk).unwrap();
+        assert_eq!(key.rank(), 2);
+        assert_eq!(key.t[0].coeffs[..2], [1337, 2073]);
+        assert_eq!(key.t[1].coeffs[..2], [1730, 1391]);
+        assert_eq!(key.a_seed[..], pk[2 * POLY_BYTES..]);
+        assert_eq!(key.to_kyber512_pk().unwrap(), pk);
+        assert_eq!(key.compress(key.a_seed).to_kyber_pk().unwrap(), pk);
+
+        let rank3 = ModuleLWEKey { a_seed: key.a_seed, t: vec![key.t[0].clone(); 3] };
+        assert!(matches!(rank3.to_kyber512_pk(), Err(ILCError::InvalidInput)));
+        assert_eq!(from_kyber_pk(&rank3.to_kyber_bytes()).unwrap().t, rank3.t);
+
+        // Rank 0 and ranks past MAX_RANK are refused
+        assert!(matches!(from_kyber_pk(&pk[pk.len() - 32..]), Err(ILCError::InvalidInput)));
+        let max = ModuleLWEKey { a_seed: key.a_seed, t: vec![key.t[0].clone(); ModuleLWEKey::MAX_RANK] };
+        assert!(from_kyber_pk(&max.to_kyber_bytes()).is_ok());
+        let over = ModuleLWEKey { a_seed: key.a_seed, t: vec![key.t[0].clone(); ModuleLWEKey::MAX_RANK + 1] };
+        assert!(matches!(from_kyber_pk(&over.to_kyber_bytes()), Err(ILCError::InvalidInput)));
+    }
+}
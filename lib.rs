@@ -25,16 +25,31 @@
 //! ## MVP Limitations
 //! 
 //! This is a minimal viable implementation:
-//! - Uses naive O(n²) polynomial multiplication (replace with NTT for production)
-//! - Simple coefficient decimation (full Gröbner-based reconstruction planned)
+//! - `RingElement::mul` is naive O(n²); `mul_ntt` is the O(n log n) alternative
+//! - Simple coefficient decimation; `compress_algebraic` drops the odd
+//!   coefficients outright but carries the secret, which cancels the
+//!   saving (full Gröbner-based reconstruction planned)
 //! - Fixed parameters (Kyber-512 compatible: n=256, q=3329)
 
 pub mod ring;
+pub mod ntt;
 pub mod types;
 pub mod sketcher;
+pub mod kyber;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "cffi")]
+pub mod cffi;
 
-pub use ring::{RingElement, N, Q};
-pub use types::{RingLWEKey, CompressedPK, AlgebraicShield, ILCError};
+pub use ring::{RingElement, GenericRingElement, BitVec, Kyber512Ring, Kyber768Ring, Kyber1024Ring, N, Q};
+pub use ntt::NttElement;
+pub use types::{RingLWEKey, ModuleLWEKey, CompressedPK, AlgebraicShield, DynStrategy, ILCError, ParityMode, ChecksumKind, SeedRef, CompressionMetrics, CompressionLevel, SizeClass, StrategyId};
+pub use types::{CorrelatedSketch, CorrelatedEntry, InterleavedPK, BalancedPK, NarrowParityPK, AlgebraicPK, SecretErrorPK, PatternedPK, CompressedDelta, SketchPatch};
+
+use bincode::Options;
+use std::sync::Arc;
 
 /// Convenience function: compress a public key polynomial
 pub fn compress(b_coeffs: &[u16; N], seed: [u8; 32]) -> CompressedPK {
@@ -50,12 +65,413 @@ pub fn decompress(sketch: &CompressedPK) -> Result<[u16; N], ILCError> {
     Ok(key.b.coeffs)
 }
 
-/// Serialize compressed key to bytes
-pub fn to_bytes(sketch: &CompressedPK) -> Vec<u8> {
-    bincode::serialize(sketch).expect("serialization should not fail")
+/// Magic prefix identifying an ILC sketch on the wire
+pub const MAGIC: [u8; 4] = *b"ILC1";
+
+/// Wire format version, sent after `MAGIC`. Bump it with any change to
+/// the encoding of `CompressedPK`.
+pub const WIRE_VERSION: u8 = 3;
+
+/// Flag bit set when the body carries the `seq` field
+pub const FLAG_SEQ: u8 = 1;
+
+/// Layout flags this build writes after the version byte, and the only
+/// ones it reads: a `seq` build and a plain build can't decode each
+/// other's bodies.
+#[cfg(feature = "seq")]
+pub const WIRE_FLAGS: u8 = FLAG_SEQ;
+#[cfg(not(feature = "seq"))]
+pub const WIRE_FLAGS: u8 = 0;
+
+/// Check the version and flags bytes following `MAGIC`
+fn check_header(version: u8, flags: u8) -> Result<(), ILCError> {
+    if version != WIRE_VERSION {
+        return Err(ILCError::UnsupportedVersion(version));
+    }
+    if flags != WIRE_FLAGS {
+        return Err(ILCError::UnsupportedFlags(flags));
+    }
+    Ok(())
+}
+
+/// Bincode settings for the body: fixed-width integers, and nothing may
+/// follow the sketch in a `from_bytes` buffer
+fn wire_options() -> impl bincode::Options {
+    bincode::DefaultOptions::new().with_fixint_encoding().reject_trailing_bytes()
+}
+
+/// Serialize compressed key to bytes, prefixed with `MAGIC`,
+/// `WIRE_VERSION` and `WIRE_FLAGS`. Fails with `Serialization` if a
+/// coefficient was never reduced mod q.
+pub fn to_bytes(sketch: &CompressedPK) -> Result<Vec<u8>, ILCError> {
+    let mut out = MAGIC.to_vec();
+    out.extend_from_slice(&[WIRE_VERSION, WIRE_FLAGS]);
+    wire_options().serialize_into(&mut out, sketch).map_err(|e| ILCError::Serialization(e.to_string()))?;
+    Ok(out)
+}
+
+/// `to_bytes`, panicking on failure. For sketches straight out of
+/// `compress`, whose coefficients are always reduced.
+pub fn to_bytes_unchecked(sketch: &CompressedPK) -> Vec<u8> {
+    to_bytes(sketch).expect("serialization should not fail")
 }
 
-/// Deserialize compressed key from bytes
+/// Deserialize compressed key from bytes, checking and stripping `MAGIC`
+/// and the version and flags bytes. Any version but `WIRE_VERSION` yields
+/// `UnsupportedVersion`, any flags but `WIRE_FLAGS` `UnsupportedFlags`; a
+/// malformed body, or bytes left over after it, yields `Deserialization`
+/// carrying the decoder's error. A body cut short carries an
+/// `io::ErrorKind::UnexpectedEof` error saying so.
 pub fn from_bytes(data: &[u8]) -> Result<CompressedPK, ILCError> {
-    bincode::deserialize(data).map_err(|_| ILCError::InvalidInput)
+    let rest = data.strip_prefix(&MAGIC[..]).ok_or(ILCError::BadMagic)?;
+    let (&[version, flags], body) = rest.split_first_chunk().ok_or(ILCError::InvalidInput)?;
+    check_header(version, flags)?;
+    wire_options().deserialize(body).map_err(|e| match *e {
+        // bincode reports a short slice with an empty message
+        bincode::ErrorKind::Io(ref io) if io.kind() == std::io::ErrorKind::UnexpectedEof => {
+            ILCError::Deserialization(Arc::new(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!("sketch truncated: {} body bytes end mid-field", body.len()),
+            )))
+        }
+        _ => ILCError::Deserialization(Arc::new(*e)),
+    })
+}
+
+/// Write the `to_bytes` encoding to `w`. Every anchor and parity vector
+/// is length-prefixed, so `read_from` knows how much to expect. A failing
+/// writer yields `Serialization` with its error message.
+pub fn write_to<W: std::io::Write>(sketch: &CompressedPK, w: &mut W) -> Result<(), ILCError> {
+    w.write_all(&MAGIC).map_err(|e| ILCError::Serialization(e.to_string()))?;
+    w.write_all(&[WIRE_VERSION, WIRE_FLAGS]).map_err(|e| ILCError::Serialization(e.to_string()))?;
+    wire_options().serialize_into(w, sketch).map_err(|e| ILCError::Serialization(e.to_string()))
+}
+
+/// Read one sketch from `r` as it arrives, without buffering the whole
+/// encoding first. Input that ends early yields `InvalidInput`; a
+/// malformed body or a failing reader yields `Deserialization` carrying
+/// the cause. Anything after the sketch is left unread.
+pub fn read_from<R: std::io::Read>(r: &mut R) -> Result<CompressedPK, ILCError> {
+    let mut header = [0u8; 6];
+    r.read_exact(&mut header).map_err(|e| read_error(bincode::ErrorKind::Io(e)))?;
+    if header[..4] != MAGIC {
+        return Err(ILCError::BadMagic);
+    }
+    check_header(header[4], header[5])?;
+    wire_options().allow_trailing_bytes().deserialize_from(r).map_err(|e| read_error(*e))
+}
+
+/// Map a decoding error from `read_from`, singling out truncation
+fn read_error(e: bincode::ErrorKind) -> ILCError {
+    match e {
+        bincode::ErrorKind::Io(ref io) if io.kind() == std::io::ErrorKind::UnexpectedEof => ILCError::InvalidInput,
+        _ => ILCError::Deserialization(Arc::new(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Expected wire size of a Kyber-512 sketch:
+    /// magic (4) + version (1) + flags (1) + seed (4-byte tag + 32) + a domain (1) + rank (1) + decimation (2) +
+    /// anchors (8-byte length + 128 * 12 bits) + checksum (8) + checksum kind (4) +
+    /// parity (8-byte length + 128 * 12 bits) + has parity (1) + parity mode (4) +
+    /// signature (1, absent).
+    ///
+    /// Changing this is a format change.
+    #[cfg(not(feature = "seq"))]
+    const KYBER512_WIRE_BYTES: usize = 464;
+    /// With the `seq` feature the sketch carries an extra u32
+    #[cfg(feature = "seq")]
+    const KYBER512_WIRE_BYTES: usize = 468;
+
+    // Golden wire encodings. A mismatch means the format or the arithmetic
+    // changed, which is a format change: treat it as one before
+    // regenerating these.
+    /// `to_bytes(&compress(&from_seed(&[0x00; 32], 1), [0x00; 32]))`
+    const GOLDEN_SEED_00: &str = concat!(
+        "494c433103000000000000000000000000000000000000000000000000000000",
+        "0000000000000000000000010200c000000000000000aea97f2c61a8add1a07d",
+        "33a663216bfb290cf4a38eba046a23135b88dc7861567eb5ac1532e4b207947b",
+        "2f657f6b6a9a1e1bb5198764e148bb66197d060a118d196bc57a066c9cc7996b",
+        "1e5c6b916c85228cec1c77aa5481b8a0f80b5672d0a95415219ef085a5e51887",
+        "ba6d5f32be733628587a3490894091186de29ab65b48b7f6e5770e4037aa7cca",
+        "afe1cf0f017547ea08f7bc28f837c8bba3846f38a19f450a91142c4a537ed364",
+        "95585c692bc2ca72576c77939f94c52e882cadd203a940974eed0f76f98f0000",
+        "0000c00000000000000068e43cd91a7f9950b876f2af4d121b369a420078cf1a",
+        "c9a00a0962bd83857f625fda8087b838c7fc08ab0b4c2984e3071fa04dfa3b88",
+        "98da0912400d2fd96dfe0883f9f35b076c7f32fb5ec94321c5119a49b895d600",
+        "1abd0486b07890a1da544aa45b4fdcab531c30d8188cc3774ad1f695193427d0",
+        "10476fa3bfdc92cb8f4621d68a44ce07ab9113049f6a6fb45ab26b064fd0db24",
+        "d408c6b46acce5764ff8c71be401861c4188842c6a20d79f7d183c2e8c1e7789",
+        "0ea74b88bc6500a49c05010000000000",
+    );
+
+    /// `to_bytes(&compress(&from_seed(&[0x42; 32], 1), [0x42; 32]))`
+    const GOLDEN_SEED_42: &str = concat!(
+        "494c433103000000000042424242424242424242424242424242424242424242",
+        "4242424242424242424200010200c0000000000000008a2c9d37e19d2a1884f1",
+        "733f9fa8922581a96d70afd9d06dbc82b5df4626a73a3bb5789b75fbcf9c3ba7",
+        "92a44bf3fbcaadd06a64a9af2857331862130955b42397726c873faed74b608c",
+        "782fe97088591f0f7116a3d909f4c2ae861b9c1af5728392858cb73ee5a65839",
+        "e343ecfc2c84a0171a954f0cc0018e935f3fd99c113395a57176b8a77eef929f",
+        "f846c87b357dacf277b90c4bcc6a0ce62cc8e65b8542193c49f69f1ffb2833c5",
+        "2154a16e8fa402086419e189be16f10a85f7cc34f34b0f4fa2212396d01a0000",
+        "0000c0000000000000004cb4450ee96be5593ce4a4692b08bd0ef3cde62618ab",
+        "375ff4a4a2be000a89074957c499f8cb68deac031ad0285bab628ac6a31823a8",
+        "0dda2293171bca140c2a318c0292869c024f79a7a7fa49ace2ca57630b7f2479",
+        "385e50b3bed54945cc578d86bfcc625a7205433b2a3d3bf1974d5a70a89639da",
+        "d56574ac2df5285de84202a3e40b98c8ac91db3ee08b8e8f3c91927675601864",
+        "42091cf9b409de99b344b44413e77666eb4c91eb68dcac11cb6683c3291dec6b",
+        "10a4b6791a3b86f4d860010000000000",
+    );
+
+    fn hex_decode(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_golden_vectors() {
+        for (seed, golden) in [([0x00u8; 32], GOLDEN_SEED_00), ([0x42u8; 32], GOLDEN_SEED_42)] {
+            let expected = hex_decode(golden);
+            // `seq` is flagged in the header and is the last field, zero
+            // unless set
+            #[cfg(feature = "seq")]
+            let expected = {
+                let mut expected = [expected, 0u32.to_le_bytes().to_vec()].concat();
+                expected[5] = FLAG_SEQ;
+                expected
+            };
+            
+            let b = RingElement::from_seed(&seed, 1);
+            let bytes = to_bytes(&compress(&b.coeffs, seed)).unwrap();
+            assert_eq!(bytes, expected);
+            assert_eq!(decompress(&from_bytes(&bytes).unwrap()).unwrap(), b.coeffs);
+        }
+    }
+
+    #[test]
+    fn test_encoding_is_deterministic() {
+        let seed = [3u8; 32];
+        let b = RingElement::from_seed(&seed, 1);
+        let bytes = to_bytes_unchecked(&compress(&b.coeffs, seed));
+        assert_eq!(to_bytes_unchecked(&compress(&b.coeffs, seed)), bytes);
+        
+        // Only length and contents reach the wire, never capacity
+        let mut roomy = compress(&b.coeffs, seed);
+        roomy.anchor_coeffs.reserve(1000);
+        roomy.parity.shrink_to_fit();
+        assert_ne!(roomy.anchor_coeffs.capacity(), roomy.parity.capacity());
+        assert_eq!(to_bytes_unchecked(&roomy), bytes);
+    }
+
+    #[test]
+    fn test_wire_format_size() {
+        let seed = [3u8; 32];
+        let b = RingElement::from_seed(&seed, 1);
+        let sketch = compress(&b.coeffs, seed);
+        assert_eq!(to_bytes(&sketch).unwrap().len(), KYBER512_WIRE_BYTES);
+    }
+
+    #[test]
+    fn test_coefficients_packed_below_raw_size() {
+        let seed = [3u8; 32];
+        let key = RingLWEKey { a: RingElement::from_seed(&seed, 0), b: RingElement::from_seed(&seed, 1) };
+        for sketch in [key.compress(seed), key.compress_anchors_only(seed)] {
+            assert!(sketch.coeff_bytes() < N * 2);
+            assert_eq!(sketch.coeff_bytes(), N * 12 / 8);
+            assert_eq!(from_bytes(&to_bytes(&sketch).unwrap()).unwrap(), sketch);
+        }
+        
+        // Odd lengths round-trip too (truncated sketches must stay
+        // representable so decompress can reject them)
+        let mut odd = key.compress(seed);
+        odd.anchor_coeffs.truncate(5);
+        assert_eq!(from_bytes(&to_bytes(&odd).unwrap()).unwrap(), odd);
+        
+        // Unreduced values can't be packed, even those that fit 12 bits
+        for bad in [Q as u16, 1 << 12] {
+            odd.parity[0] = bad;
+            assert!(bincode::serialize(&odd).is_err());
+            assert!(matches!(to_bytes(&odd), Err(ILCError::Serialization(_))));
+            assert!(matches!(write_to(&odd, &mut Vec::new()), Err(ILCError::Serialization(_))));
+        }
+        
+        // Nor decoded: the first anchor's top bits (byte 55, after header,
+        // seed, a domain, rank, decimation and the length) set to reach q
+        let mut bytes = to_bytes(&key.compress(seed)).unwrap();
+        bytes[54] = (Q & 0xff) as u8;
+        bytes[55] = (bytes[55] & 0xf0) | (Q >> 8) as u8;
+        let err = from_bytes(&bytes).unwrap_err();
+        assert!(err.to_string().contains("not reduced mod q"), "{err}");
+    }
+
+    #[test]
+    fn test_stream_roundtrip() {
+        let sketches: Vec<CompressedPK> = (0..3u8)
+            .map(|i| compress(&RingElement::from_seed(&[i; 32], 1).coeffs, [i; 32]))
+            .collect();
+        let mut wire = Vec::new();
+        for sketch in &sketches {
+            write_to(sketch, &mut wire).unwrap();
+        }
+        assert_eq!(wire[..KYBER512_WIRE_BYTES], to_bytes(&sketches[0]).unwrap());
+        
+        // Back-to-back sketches are read one at a time
+        let mut cursor = Cursor::new(wire);
+        for sketch in &sketches {
+            assert_eq!(read_from(&mut cursor).unwrap(), *sketch);
+        }
+        assert!(matches!(read_from(&mut cursor), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_stream_truncated() {
+        let seed = [3u8; 32];
+        let bytes = to_bytes_unchecked(&compress(&RingElement::from_seed(&seed, 1).coeffs, seed));
+        for len in [2, 4, 40, bytes.len() / 2, bytes.len() - 1] {
+            let mut cursor = Cursor::new(&bytes[..len]);
+            assert!(matches!(read_from(&mut cursor), Err(ILCError::InvalidInput)), "len {len}");
+        }
+        let mut cursor = Cursor::new(&b"ILC0rest"[..]);
+        assert!(matches!(read_from(&mut cursor), Err(ILCError::BadMagic)));
+        let mut cursor = Cursor::new(&b"ILC1\x07\x00rest"[..]);
+        assert!(matches!(read_from(&mut cursor), Err(ILCError::UnsupportedVersion(7))));
+    }
+
+    /// Reader and writer that fail with something other than end of input
+    struct Broken;
+
+    impl std::io::Read for Broken {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(std::io::ErrorKind::ConnectionReset, "link down"))
+        }
+    }
+
+    impl std::io::Write for Broken {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "link down"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_stream_reports_cause() {
+        use std::error::Error;
+        
+        let seed = [3u8; 32];
+        let sketch = compress(&RingElement::from_seed(&seed, 1).coeffs, seed);
+        
+        let err = read_from(&mut Broken).unwrap_err();
+        assert!(matches!(err, ILCError::Deserialization(_)));
+        assert!(err.source().is_some());
+        assert!(err.to_string().contains("link down"), "{err}");
+        
+        // A reader failing mid-body, after a good header
+        let bytes = to_bytes_unchecked(&sketch);
+        let err = read_from(&mut std::io::Read::chain(&bytes[..40], Broken)).unwrap_err();
+        assert!(err.to_string().contains("link down"), "{err}");
+        
+        // A malformed body is not mistaken for truncation
+        let mut corrupt = bytes;
+        let has_parity = corrupt.len() - 6 - if cfg!(feature = "seq") { 4 } else { 0 };
+        corrupt[has_parity] = 2;
+        let err = read_from(&mut Cursor::new(&corrupt)).unwrap_err();
+        assert!(err.to_string().contains("expected 0 or 1, found 2"), "{err}");
+        
+        let err = write_to(&sketch, &mut Broken).unwrap_err();
+        assert!(matches!(err, ILCError::Serialization(ref msg) if msg.contains("link down")), "{err}");
+    }
+
+    #[test]
+    fn test_corrupt_body_reports_cause() {
+        use std::error::Error;
+        
+        let seed = [3u8; 32];
+        let bytes = to_bytes_unchecked(&compress(&RingElement::from_seed(&seed, 1).coeffs, seed));
+        
+        let err = from_bytes(&bytes[..bytes.len() / 2]).unwrap_err();
+        assert!(matches!(err, ILCError::Deserialization(_)));
+        let cause = err.source().and_then(|e| e.downcast_ref::<std::io::Error>()).unwrap();
+        assert_eq!(cause.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert!(!cause.to_string().is_empty());
+        
+        // has_parity sits before the parity mode (4) and absent signature (1)
+        let mut corrupt = bytes.clone();
+        let has_parity = bytes.len() - 6 - if cfg!(feature = "seq") { 4 } else { 0 };
+        corrupt[has_parity] = 2;
+        let err = from_bytes(&corrupt).unwrap_err();
+        assert!(err.to_string().contains("expected 0 or 1, found 2"), "{err}");
+    }
+
+    #[test]
+    fn test_magic_prefix() {
+        let seed = [3u8; 32];
+        let b = RingElement::from_seed(&seed, 1);
+        let bytes = to_bytes(&compress(&b.coeffs, seed)).unwrap();
+        assert_eq!(bytes[..4], MAGIC);
+        assert_eq!(bytes[4], WIRE_VERSION);
+        assert_eq!(bytes[5], WIRE_FLAGS);
+        assert_eq!(decompress(&from_bytes(&bytes).unwrap()).unwrap(), b.coeffs);
+
+        // Raw bincode without the prefix is rejected
+        let raw = bincode::serialize(&compress(&b.coeffs, seed)).unwrap();
+        assert!(matches!(from_bytes(&raw), Err(ILCError::BadMagic)));
+        assert!(matches!(from_bytes(b"IL"), Err(ILCError::BadMagic)));
+    }
+
+    #[test]
+    fn test_version_byte() {
+        let seed = [3u8; 32];
+        let bytes = to_bytes_unchecked(&compress(&RingElement::from_seed(&seed, 1).coeffs, seed));
+        
+        // A future encoder's output is refused up front, not misparsed
+        let mut future = bytes.clone();
+        future[4] = WIRE_VERSION + 1;
+        assert!(matches!(from_bytes(&future), Err(ILCError::UnsupportedVersion(v)) if v == WIRE_VERSION + 1));
+        assert!(matches!(from_bytes(&MAGIC), Err(ILCError::InvalidInput)));
+        assert!(matches!(from_bytes(&bytes[..5]), Err(ILCError::InvalidInput)));
+        
+        // Wrong magic is reported before the version is looked at
+        let mut foreign = future;
+        foreign[..4].copy_from_slice(b"XYZ1");
+        assert!(matches!(from_bytes(&foreign), Err(ILCError::BadMagic)));
+    }
+
+    #[test]
+    fn test_seq_layout_is_flagged() {
+        let seed = [3u8; 32];
+        let bytes = to_bytes_unchecked(&compress(&RingElement::from_seed(&seed, 1).coeffs, seed));
+        
+        // What the build with the other `seq` setting would send: the flag
+        // toggled and the trailing u32 added or dropped
+        let mut other = bytes.clone();
+        other[5] ^= FLAG_SEQ;
+        if cfg!(feature = "seq") {
+            other.truncate(other.len() - 4);
+        } else {
+            other.extend_from_slice(&7u32.to_le_bytes());
+        }
+        assert!(matches!(from_bytes(&other), Err(ILCError::UnsupportedFlags(f)) if f == WIRE_FLAGS ^ FLAG_SEQ));
+        assert!(matches!(read_from(&mut Cursor::new(&other)), Err(ILCError::UnsupportedFlags(_))));
+    }
+
+    #[test]
+    fn test_trailing_bytes_rejected() {
+        let seed = [3u8; 32];
+        let mut bytes = to_bytes_unchecked(&compress(&RingElement::from_seed(&seed, 1).coeffs, seed));
+        bytes.push(0);
+        assert!(matches!(from_bytes(&bytes), Err(ILCError::Deserialization(_))));
+        
+        // A stream may carry more after the sketch
+        assert!(read_from(&mut Cursor::new(&bytes)).is_ok());
+    }
 }
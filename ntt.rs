@@ -0,0 +1,251 @@
+//! Number-theoretic transform for Z_q[X]/(X^n + 1), n = 256, q = 3329
+//!
+//! q - 1 = 2^8 * 13, so Z_q has primitive 256th roots of unity (17 is one)
+//! but no 512th root. The negacyclic ring therefore can't be split all the
+//! way into linear factors: as in Kyber, seven layers split X^256 + 1 into
+//! 128 quadratics X^2 - zeta^(2*br7(i) + 1), and products are taken
+//! pairwise in that domain (`basemul`).
+
+use crate::ring::{barrett_reduce, montgomery_reduce, RingElement, MONT_R2, N, Q};
+
+/// Primitive 256th root of unity mod q
+pub const ZETA: u32 = 17;
+
+/// 128^-1 mod q, undoing the seven inverse layers
+const INV_128: u32 = 3303;
+
+const fn pow_mod(mut base: u32, mut exp: u32) -> u32 {
+    let mut acc = 1;
+    base %= Q;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = acc * base % Q;
+        }
+        base = base * base % Q;
+        exp >>= 1;
+    }
+    acc
+}
+
+/// 7-bit reversal
+const fn br7(i: usize) -> u32 {
+    (i as u8).reverse_bits() as u32 >> 1
+}
+
+/// `ZETAS[i] = 17^br7(i) mod q`, the twiddle for butterfly block `i`
+pub const ZETAS: [u16; 128] = {
+    let mut t = [0u16; 128];
+    let mut i = 0;
+    while i < 128 {
+        t[i] = pow_mod(ZETA, br7(i)) as u16;
+        i += 1;
+    }
+    t
+};
+
+/// `ZETAS_INV[i] = ZETAS[i]^-1 mod q`
+const ZETAS_INV: [u16; 128] = {
+    let mut t = [0u16; 128];
+    let mut i = 0;
+    while i < 128 {
+        t[i] = pow_mod(ZETAS[i] as u32, Q - 2) as u16;
+        i += 1;
+    }
+    t
+};
+
+/// Forward transform in place. Input coefficients must be reduced; the
+/// output is in Kyber's bit-reversed pair order.
+pub fn forward(r: &mut [u16; N]) {
+    let mut k = 1;
+    let mut len = N / 2;
+    while len >= 2 {
+        for start in (0..N).step_by(2 * len) {
+            let zeta = ZETAS[k] as u32;
+            k += 1;
+            for j in start..start + len {
+                let t = zeta * r[j + len] as u32 % Q;
+                r[j + len] = ((r[j] as u32 + Q - t) % Q) as u16;
+                r[j] = ((r[j] as u32 + t) % Q) as u16;
+            }
+        }
+        len /= 2;
+    }
+}
+
+/// Inverse of `forward`, including the 1/128 scaling
+pub fn inverse(r: &mut [u16; N]) {
+    let mut len = 2;
+    while len <= N / 2 {
+        // Undo each layer's butterflies with the twiddles `forward` used:
+        // the layer with half-size `len` has N / (2 * len) blocks
+        // starting at index N / (2 * len)
+        let first = N / (2 * len);
+        for (block, start) in (0..N).step_by(2 * len).enumerate() {
+            let zeta_inv = ZETAS_INV[first + block] as u32;
+            for j in start..start + len {
+                let (a, b) = (r[j] as u32, r[j + len] as u32);
+                r[j] = ((a + b) % Q) as u16;
+                r[j + len] = ((a + Q - b) * zeta_inv % Q) as u16;
+            }
+        }
+        len *= 2;
+    }
+    for c in r.iter_mut() {
+        *c = (*c as u32 * INV_128 % Q) as u16;
+    }
+}
+
+/// `ZETAS` in Montgomery form, for `fqmul` against plain values
+const ZETAS_MONT: [i16; 128] = {
+    let mut t = [0i16; 128];
+    let mut i = 0;
+    while i < 128 {
+        t[i] = (ZETAS[i] as u32 * 65536 % Q) as i16;
+        i += 1;
+    }
+    t
+};
+
+/// Montgomery product `a * b * R^-1`. With one operand in Montgomery form
+/// the result is the plain product, in (-q, q).
+#[inline]
+fn fqmul(a: i16, b: i16) -> i16 {
+    montgomery_reduce(a as i32 * b as i32)
+}
+
+/// Pointwise product of two transformed polynomials: each pair of
+/// coefficients is multiplied mod X^2 - gamma for its quadratic factor.
+/// Inputs must be reduced. `a` is lifted into Montgomery form so every
+/// product is a single `fqmul` with no division.
+pub fn basemul(a: &[u16; N], b: &[u16; N]) -> [u16; N] {
+    let mut out = [0u16; N];
+    let am = a.map(|c| montgomery_reduce(c as i32 * MONT_R2));
+    let b = b.map(|c| c as i16);
+    for i in 0..N / 4 {
+        let zeta = ZETAS_MONT[64 + i];
+        for (base, gamma) in [(4 * i, zeta), (4 * i + 2, -zeta)] {
+            let (a0, a1) = (am[base], am[base + 1]);
+            let (b0, b1) = (b[base], b[base + 1]);
+            // Each fqmul is in (-q, q), so the sums stay in (-2q, 2q)
+            let r0 = fqmul(a0, b0) + fqmul(fqmul(a1, b1), gamma);
+            let r1 = fqmul(a0, b1) + fqmul(a1, b0);
+            out[base] = barrett_reduce((r0 + 2 * Q as i16) as u32);
+            out[base + 1] = barrett_reduce((r1 + 2 * Q as i16) as u32);
+        }
+    }
+    out
+}
+
+/// Polynomial in the NTT domain, from `RingElement::to_ntt`. Only the
+/// operations that are valid pointwise are offered, so NTT-form data can't
+/// be fed to the schoolbook `mul` by mistake.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NttElement {
+    coeffs: [u16; N],
+}
+
+impl NttElement {
+    /// Pointwise addition
+    pub fn add(&self, other: &Self) -> Self {
+        let mut coeffs = [0u16; N];
+        for (c, (&x, &y)) in coeffs.iter_mut().zip(self.coeffs.iter().zip(&other.coeffs)) {
+            *c = ((x as u32 + y as u32) % Q) as u16;
+        }
+        Self { coeffs }
+    }
+
+    /// Pointwise subtraction
+    pub fn sub(&self, other: &Self) -> Self {
+        let mut coeffs = [0u16; N];
+        for (c, (&x, &y)) in coeffs.iter_mut().zip(self.coeffs.iter().zip(&other.coeffs)) {
+            *c = ((x as u32 + Q - y as u32) % Q) as u16;
+        }
+        Self { coeffs }
+    }
+
+    /// Ring product, computed pairwise with `basemul`
+    pub fn mul(&self, other: &Self) -> Self {
+        Self { coeffs: basemul(&self.coeffs, &other.coeffs) }
+    }
+
+    /// Transform back to coefficient form
+    pub fn from_ntt(self) -> RingElement {
+        let mut coeffs = self.coeffs;
+        inverse(&mut coeffs);
+        RingElement::new(coeffs)
+    }
+}
+
+impl RingElement {
+    /// Transform into the NTT domain, where products are pointwise
+    pub fn to_ntt(self) -> NttElement {
+        let mut coeffs = self.coeffs;
+        forward(&mut coeffs);
+        NttElement { coeffs }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zeta_is_primitive_256th_root() {
+        assert_eq!(pow_mod(ZETA, 256), 1);
+        assert_eq!(pow_mod(ZETA, 128), Q - 1);
+        assert_eq!(ZETAS[1], 1729);
+    }
+
+    #[test]
+    fn test_forward_inverse_roundtrip() {
+        let a = RingElement::from_seed(&[8u8; 32], 0);
+        let mut r = a.coeffs;
+        forward(&mut r);
+        assert_ne!(r, a.coeffs);
+        inverse(&mut r);
+        assert_eq!(r, a.coeffs);
+    }
+
+    /// `basemul` with plain `%` arithmetic
+    fn basemul_reference(a: &[u16; N], b: &[u16; N]) -> [u16; N] {
+        let mut out = [0u16; N];
+        for i in 0..N / 4 {
+            let zeta = ZETAS[64 + i] as u32;
+            for (base, gamma) in [(4 * i, zeta), (4 * i + 2, Q - zeta)] {
+                let (a0, a1) = (a[base] as u32, a[base + 1] as u32);
+                let (b0, b1) = (b[base] as u32, b[base + 1] as u32);
+                out[base] = ((a0 * b0 + a1 * b1 % Q * gamma) % Q) as u16;
+                out[base + 1] = ((a0 * b1 + a1 * b0) % Q) as u16;
+            }
+        }
+        out
+    }
+
+    #[test]
+    fn test_montgomery_basemul_matches_reference() {
+        for domain in 0..20 {
+            let a = RingElement::from_seed(&[10u8; 32], domain).coeffs;
+            let b = RingElement::from_seed(&[11u8; 32], domain).coeffs;
+            assert_eq!(basemul(&a, &b), basemul_reference(&a, &b));
+        }
+        // Extremes of the reduced range
+        let top = [Q as u16 - 1; N];
+        assert_eq!(basemul(&top, &top), basemul_reference(&top, &top));
+        assert_eq!(basemul(&top, &[0; N]), [0; N]);
+    }
+
+    #[test]
+    fn test_rlwe_in_ntt_domain() {
+        let seed = [9u8; 32];
+        let a = RingElement::from_seed(&seed, 0);
+        let s = RingElement::from_seed(&seed, 1);
+        let e = RingElement::from_seed(&seed, 2);
+        
+        let b = a.clone().to_ntt().mul(&s.clone().to_ntt()).add(&e.clone().to_ntt()).from_ntt();
+        assert_eq!(b, a.mul(&s).add(&e));
+        
+        let diff = b.clone().to_ntt().sub(&e.to_ntt()).from_ntt();
+        assert_eq!(diff, a.mul(&s));
+    }
+}
@@ -0,0 +1,201 @@
+//! Protobuf encoding of `CompressedPK` for cross-language consumers
+//!
+//! Enabled by the `protobuf` feature. The message layout is `PROTO_SCHEMA`;
+//! proto3 defaults keep the common case (inline seed, domain 0, one
+//! polynomial, `Sum` parity, SHA3 checksum, no signature) down to seed,
+//! anchors, parity and checksum.
+
+use crate::ring::{N, Q};
+use crate::types::{ChecksumKind, CompressedPK, ILCError, ParityMode, SeedRef};
+use prost::Message;
+
+/// Schema matching `CompressedPkProto`, for generating bindings elsewhere
+pub const PROTO_SCHEMA: &str = r#"syntax = "proto3";
+
+package ilc;
+
+message CompressedPK {
+  bytes seed = 1;                 // 32-byte inline seed, empty if seed_id is set
+  repeated uint32 anchors = 2;    // packed, each < q
+  repeated uint32 parity = 3;     // packed, each < q
+  bytes checksum = 4;             // 8 bytes
+  optional uint32 seed_id = 5;    // well-known seed id instead of an inline seed
+  uint32 a_domain = 6;
+  uint32 parity_mode = 7;         // 0 = sum, 1 = difference
+  bool no_parity = 8;             // anchors hold all N coefficients
+  optional bytes signature = 9;
+  uint32 seq = 10;                // only with the `seq` feature
+  uint32 rank = 11;               // polynomials sketched, 0 read as 1
+  uint32 decimation = 12;         // anchor stride, 0 read as 2
+  uint32 checksum_kind = 13;      // 0 = sha3, 1 = crc32, 2 = none, 3 = mac, 4 = chained
+}
+"#;
+
+/// Wire message for `PROTO_SCHEMA`
+#[derive(Clone, PartialEq, Message)]
+struct CompressedPkProto {
+    #[prost(bytes = "vec", tag = "1")]
+    seed: Vec<u8>,
+    #[prost(uint32, repeated, tag = "2")]
+    anchors: Vec<u32>,
+    #[prost(uint32, repeated, tag = "3")]
+    parity: Vec<u32>,
+    #[prost(bytes = "vec", tag = "4")]
+    checksum: Vec<u8>,
+    #[prost(uint32, optional, tag = "5")]
+    seed_id: Option<u32>,
+    #[prost(uint32, tag = "6")]
+    a_domain: u32,
+    #[prost(uint32, tag = "7")]
+    parity_mode: u32,
+    #[prost(bool, tag = "8")]
+    no_parity: bool,
+    #[prost(bytes = "vec", optional, tag = "9")]
+    signature: Option<Vec<u8>>,
+    #[prost(uint32, tag = "10")]
+    seq: u32,
+    #[prost(uint32, tag = "11")]
+    rank: u32,
+    #[prost(uint32, tag = "12")]
+    decimation: u32,
+    #[prost(uint32, tag = "13")]
+    checksum_kind: u32,
+}
+
+/// Narrow protobuf coefficients back to u16, rejecting values >= q and
+/// vectors longer than `max_len`
+fn coeffs_from_proto(values: &[u32], max_len: usize) -> Result<Vec<u16>, ILCError> {
+    if values.len() > max_len || values.iter().any(|&v| v >= Q) {
+        return Err(ILCError::InvalidInput);
+    }
+    Ok(values.iter().map(|&v| v as u16).collect())
+}
+
+impl CompressedPK {
+    /// Encode as a `PROTO_SCHEMA` message
+    pub fn to_protobuf(&self) -> Vec<u8> {
+        let (seed, seed_id) = match self.seed {
+            SeedRef::Inline(seed) => (seed.to_vec(), None),
+            SeedRef::Id(id) => (Vec::new(), Some(id)),
+        };
+        CompressedPkProto {
+            seed,
+            anchors: self.anchor_coeffs.iter().map(|&c| c as u32).collect(),
+            parity: self.parity.iter().map(|&c| c as u32).collect(),
+            checksum: self.checksum.to_vec(),
+            seed_id,
+            a_domain: self.a_domain as u32,
+            parity_mode: match self.parity_mode {
+                ParityMode::Sum => 0,
+                ParityMode::Difference => 1,
+            },
+            no_parity: !self.has_parity,
+            signature: self.signature.clone(),
+            #[cfg(feature = "seq")]
+            seq: self.seq,
+            #[cfg(not(feature = "seq"))]
+            seq: 0,
+            // Left at the default for the common single-polynomial case
+            rank: if self.rank == 1 { 0 } else { self.rank as u32 },
+            decimation: if self.decimation == 2 { 0 } else { self.decimation as u32 },
+            checksum_kind: match self.checksum_kind {
+                ChecksumKind::Sha3 => 0,
+                ChecksumKind::Crc32 => 1,
+                ChecksumKind::None => 2,
+                ChecksumKind::Mac => 3,
+                ChecksumKind::Chained => 4,
+            },
+        }
+        .encode_to_vec()
+    }
+
+    /// Decode a `PROTO_SCHEMA` message. Malformed messages or fields out
+    /// of range yield `InvalidInput`; the sketch itself is verified as
+    /// usual on decompression.
+    pub fn from_protobuf(bytes: &[u8]) -> Result<Self, ILCError> {
+        let msg = CompressedPkProto::decode(bytes).map_err(|_| ILCError::InvalidInput)?;
+
+        let seed = match msg.seed_id {
+            Some(id) if msg.seed.is_empty() => SeedRef::Id(id),
+            None => SeedRef::Inline(msg.seed.try_into().map_err(|_| ILCError::InvalidInput)?),
+            Some(_) => return Err(ILCError::InvalidInput),
+        };
+        let rank = match msg.rank {
+            0 => 1,
+            k => u8::try_from(k).map_err(|_| ILCError::InvalidInput)?,
+        };
+        let parity_mode = match msg.parity_mode {
+            0 => ParityMode::Sum,
+            1 => ParityMode::Difference,
+            _ => return Err(ILCError::InvalidInput),
+        };
+        let checksum_kind = match msg.checksum_kind {
+            0 => ChecksumKind::Sha3,
+            1 => ChecksumKind::Crc32,
+            2 => ChecksumKind::None,
+            3 => ChecksumKind::Mac,
+            4 => ChecksumKind::Chained,
+            _ => return Err(ILCError::InvalidInput),
+        };
+
+        Ok(CompressedPK {
+            seed,
+            a_domain: u8::try_from(msg.a_domain).map_err(|_| ILCError::InvalidInput)?,
+            rank,
+            decimation: match msg.decimation {
+                0 => 2,
+                k => u16::try_from(k).map_err(|_| ILCError::InvalidInput)?,
+            },
+            anchor_coeffs: coeffs_from_proto(&msg.anchors, rank as usize * N)?,
+            checksum: msg.checksum.try_into().map_err(|_| ILCError::InvalidInput)?,
+            checksum_kind,
+            parity: coeffs_from_proto(&msg.parity, rank as usize * N)?,
+            has_parity: !msg.no_parity,
+            parity_mode,
+            signature: msg.signature,
+            #[cfg(feature = "seq")]
+            seq: msg.seq,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CompressedPkProto;
+    use crate::ring::{RingElement, Q};
+    use crate::types::{AlgebraicShield, ChecksumKind, CompressedPK, ILCError, ModuleLWEKey, ParityMode, RingLWEKey};
+    use prost::Message;
+
+    #[test]
+    fn test_protobuf_roundtrip() {
+        let seed = [7u8; 32];
+        let key = RingLWEKey {
+            a: RingElement::from_seed(&seed, 0),
+            b: RingElement::from_seed(&seed, 1),
+        };
+
+        for sketch in [
+            key.compress(seed),
+            key.compress_with_parity_mode(seed, ParityMode::Difference).with_seed_id(3),
+            key.compress_anchors_only(seed),
+            key.compress_with_checksum(seed, ChecksumKind::Crc32).unwrap(),
+            key.compress_with_mac(seed, b"k"),
+            ModuleLWEKey { a_seed: seed, t: vec![key.b.clone(); 3] }.compress(seed),
+        ] {
+            let decoded = CompressedPK::from_protobuf(&sketch.to_protobuf()).unwrap();
+            assert_eq!(decoded, sketch);
+        }
+
+        let decoded = CompressedPK::from_protobuf(&key.compress(seed).to_protobuf()).unwrap();
+        assert_eq!(RingLWEKey::decompress(&decoded).unwrap().b, key.b);
+
+        assert!(CompressedPK::from_protobuf(&[0xff]).is_err());
+
+        // Coefficients must be reduced, as the schema says
+        for q_or_more in [Q, u16::MAX as u32, u32::MAX] {
+            let mut msg = CompressedPkProto::decode(&key.compress(seed).to_protobuf()[..]).unwrap();
+            msg.parity[5] = q_or_more;
+            assert!(matches!(CompressedPK::from_protobuf(&msg.encode_to_vec()), Err(ILCError::InvalidInput)));
+        }
+    }
+}
@@ -1,78 +1,218 @@
 //! Ring arithmetic for Z_q[X]/(X^n + 1)
 //! 
-//! MVP: Uses naive polynomial multiplication. 
-//! Production: Replace with NTT for O(n log n) performance.
+//! `mul` is the naive O(n^2) product; `mul_ntt` is the O(n log n)
+//! NTT-based one.
+
+use crate::types::ILCError;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 /// Ring parameters (Kyber-512 compatible)
 pub const N: usize = 256;
 pub const Q: u32 = 3329;
 
-/// Polynomial in Z_q[X]/(X^n + 1)
+// Counts `from_seed` calls on this thread, so tests can observe when the
+// expensive regeneration of 'a' is skipped
+#[cfg(test)]
+thread_local! {
+    pub(crate) static FROM_SEED_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// Bitstream view of a polynomial, one `bool` per bit
+pub type BitVec = Vec<bool>;
+
+/// Bits per coefficient in `to_bits`, enough for any value below q
+pub const COEFF_BITS: usize = 12;
+
+/// Polynomial in Z_q[X]/(X^n + 1) for any n and q. Only `new`, `add`,
+/// `sub`, `mul` and `from_seed` are generic; everything else, and the
+/// rest of the crate, works on `RingElement`.
+///
+/// Coefficients are `u16`, so q may be at most 2^16; a larger modulus is
+/// rejected at compile time:
+///
+/// ```compile_fail
+/// use ilc_rs::ring::GenericRingElement;
+/// let _ = GenericRingElement::<4, 70000>::new([0; 4]);
+/// ```
 #[derive(Clone, Debug, PartialEq)]
-pub struct RingElement {
-    pub coeffs: [u16; N],
+pub struct GenericRingElement<const DEG: usize, const MOD: u32> {
+    pub coeffs: [u16; DEG],
 }
 
-impl Default for RingElement {
+/// Polynomial in Z_q[X]/(X^n + 1) with the crate's `N` and `Q`
+pub type RingElement = GenericRingElement<N, Q>;
+
+/// Ring of a Kyber-512 public key polynomial
+pub type Kyber512Ring = GenericRingElement<256, 3329>;
+/// Kyber-768 shares n and q with Kyber-512; only the module rank differs
+pub type Kyber768Ring = GenericRingElement<256, 3329>;
+/// Kyber-1024 shares n and q with Kyber-512; only the module rank differs
+pub type Kyber1024Ring = GenericRingElement<256, 3329>;
+
+impl<const DEG: usize, const MOD: u32> Default for GenericRingElement<DEG, MOD> {
     fn default() -> Self {
-        Self { coeffs: [0u16; N] }
+        Self { coeffs: [0u16; DEG] }
     }
 }
 
-impl RingElement {
-    pub fn new(coeffs: [u16; N]) -> Self {
+#[cfg(feature = "zeroize")]
+impl<const DEG: usize, const MOD: u32> zeroize::Zeroize for GenericRingElement<DEG, MOD> {
+    fn zeroize(&mut self) {
+        self.coeffs.zeroize();
+    }
+}
+
+/// With `zeroize`, every ring element is wiped on drop: public values
+/// are indistinguishable from secrets and errors at the type level
+#[cfg(feature = "zeroize")]
+impl<const DEG: usize, const MOD: u32> Drop for GenericRingElement<DEG, MOD> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<const DEG: usize, const MOD: u32> zeroize::ZeroizeOnDrop for GenericRingElement<DEG, MOD> {}
+
+/// Serialized as the plain sequence of DEG coefficients, e.g. a JSON
+/// array. Unlike `CompressedPK` there is no 12-bit packing; this is for
+/// persisting and inspecting keys, not for the wire.
+impl<const DEG: usize, const MOD: u32> Serialize for GenericRingElement<DEG, MOD> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.coeffs[..].serialize(serializer)
+    }
+}
+
+/// Rejects sequences of the wrong length and coefficients >= q, so a
+/// deserialized element is always reduced
+impl<'de, const DEG: usize, const MOD: u32> Deserialize<'de> for GenericRingElement<DEG, MOD> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let coeffs = Vec::<u16>::deserialize(deserializer)?;
+        let len = coeffs.len();
+        let coeffs: [u16; DEG] = coeffs
+            .try_into()
+            .map_err(|_| de::Error::invalid_length(len, &"one value per coefficient"))?;
+        if let Some(&c) = coeffs.iter().find(|&&c| c as u32 >= MOD) {
+            return Err(de::Error::invalid_value(de::Unexpected::Unsigned(c as u64), &"a coefficient below q"));
+        }
+        Ok(Self { coeffs })
+    }
+}
+
+impl<'a, const DEG: usize, const MOD: u32> IntoIterator for &'a GenericRingElement<DEG, MOD> {
+    type Item = &'a u16;
+    type IntoIter = std::slice::Iter<'a, u16>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.coeffs.iter()
+    }
+}
+
+impl<const DEG: usize, const MOD: u32> GenericRingElement<DEG, MOD> {
+    /// Every residue mod q must fit the u16 coefficients
+    const MOD_FITS: () = assert!(MOD >= 2 && MOD <= 1 << 16, "q must be between 2 and 2^16");
+
+    /// Every schoolbook column sum must fit the u32 `reduce_mod` takes
+    const PARAMS_FIT: () = assert!(
+        (DEG as u64) * (MOD as u64 - 1) * (MOD as u64 - 1) < 1 << 32,
+        "n * (q - 1)^2 must fit in a u32"
+    );
+
+    pub fn new(coeffs: [u16; DEG]) -> Self {
+        let () = Self::MOD_FITS;
         Self { coeffs }
     }
 
     /// Reduce all coefficients mod q
     pub fn reduce(&mut self) {
         for c in &mut self.coeffs {
-            *c = (*c as u32 % Q) as u16;
+            *c = reduce_mod::<MOD>(*c as u32);
         }
     }
 
-    /// Add two ring elements
+    /// Add two ring elements. Coefficients must already be reduced, so
+    /// each sum is below 2q and one conditional subtraction replaces `% Q`.
     pub fn add(&self, other: &Self) -> Self {
+        let () = Self::MOD_FITS;
         let mut result = Self::default();
-        for i in 0..N {
-            result.coeffs[i] = ((self.coeffs[i] as u32 + other.coeffs[i] as u32) % Q) as u16;
-        }
+        add_dispatch::<MOD>(&mut result.coeffs, &self.coeffs, &other.coeffs);
         result
     }
 
     /// Subtract two ring elements
     pub fn sub(&self, other: &Self) -> Self {
+        let () = Self::MOD_FITS;
         let mut result = Self::default();
-        for i in 0..N {
-            result.coeffs[i] = ((self.coeffs[i] as u32 + Q - other.coeffs[i] as u32) % Q) as u16;
-        }
+        sub_dispatch::<MOD>(&mut result.coeffs, &self.coeffs, &other.coeffs);
         result
     }
 
     /// Naive polynomial multiplication in Z_q[X]/(X^n + 1)
     /// MVP implementation - O(n^2). Replace with NTT for production.
     pub fn mul(&self, other: &Self) -> Self {
-        let mut result = [0i64; 2 * N];
-        
-        // Standard polynomial multiplication
-        for i in 0..N {
-            for j in 0..N {
-                result[i + j] += (self.coeffs[i] as i64) * (other.coeffs[j] as i64);
+        let (lo, hi) = self.schoolbook(other);
+        Self::fold_negacyclic(&lo, &hi)
+    }
+
+    /// `mul` for parameter sets too large for `mul`, which rejects them at
+    /// compile time: column sums are accumulated with overflow checks and
+    /// `Overflow` is returned if one doesn't fit a u32.
+    #[cfg(feature = "checked")]
+    pub fn try_mul(&self, other: &Self) -> Result<Self, ILCError> {
+        let (mut x, mut y) = (self.clone(), other.clone());
+        x.reduce();
+        y.reduce();
+        let (mut lo, mut hi) = ([0u32; DEG], [0u32; DEG]);
+        for i in 0..DEG {
+            for j in 0..DEG {
+                let p = (x.coeffs[i] as u32) * (y.coeffs[j] as u32);
+                let acc = if i + j < DEG { &mut lo[i + j] } else { &mut hi[i + j - DEG] };
+                *acc = acc.checked_add(p).ok_or(ILCError::Overflow)?;
             }
         }
-        
-        // Reduce by X^n + 1 (coefficients at index >= N wrap with negation)
+        Ok(Self::fold_negacyclic(&lo, &hi))
+    }
+
+    /// Combine the halves of a standard product: X^n = -1, so the upper
+    /// half is subtracted
+    fn fold_negacyclic(lo: &[u32; DEG], hi: &[u32; DEG]) -> Self {
         let mut out = Self::default();
-        for i in 0..N {
-            let val = result[i] - result[i + N];
-            // Handle negative values
-            out.coeffs[i] = ((val % Q as i64 + Q as i64) % Q as i64) as u16;
+        for i in 0..DEG {
+            let (lo, hi) = (reduce_mod::<MOD>(lo[i]), reduce_mod::<MOD>(hi[i]));
+            out.coeffs[i] = csub_mod::<MOD>(lo as u32 + MOD - hi as u32);
         }
         out
     }
 
+    /// Standard polynomial product, split into the coefficients of
+    /// X^0..X^(n-1) and X^n..X^(2n-1). Operands are reduced first, so
+    /// every entry is at most n * (q - 1)^2 < 2^32 and stays in the range
+    /// `reduce_mod` accepts.
+    fn schoolbook(&self, other: &Self) -> ([u32; DEG], [u32; DEG]) {
+        let () = Self::PARAMS_FIT;
+        let (mut x, mut y) = (self.clone(), other.clone());
+        x.reduce();
+        y.reduce();
+        let (mut lo, mut hi) = ([0u32; DEG], [0u32; DEG]);
+        for i in 0..DEG {
+            for j in 0..DEG {
+                let p = (x.coeffs[i] as u32) * (y.coeffs[j] as u32);
+                if i + j < DEG {
+                    lo[i + j] += p;
+                } else {
+                    hi[i + j - DEG] += p;
+                }
+            }
+        }
+        (lo, hi)
+    }
+
     /// Generate deterministically from seed
     pub fn from_seed(seed: &[u8; 32], domain: u8) -> Self {
+        let () = Self::MOD_FITS;
+        #[cfg(test)]
+        FROM_SEED_CALLS.with(|c| c.set(c.get() + 1));
+        
         use rand::SeedableRng;
         use rand_chacha::ChaCha20Rng;
         use sha3::{Shake128, digest::{ExtendableOutput, Update, XofReader}};
@@ -87,36 +227,927 @@ impl RingElement {
         reader.read(&mut derived_seed);
         
         let mut rng = ChaCha20Rng::from_seed(derived_seed);
-        let mut coeffs = [0u16; N];
+        let mut coeffs = [0u16; DEG];
         
         use rand::Rng;
         for c in &mut coeffs {
-            *c = (rng.gen::<u16>() as u32 % Q) as u16;
+            *c = reduce_mod::<MOD>(rng.gen::<u16>() as u32);
         }
         
         Self { coeffs }
     }
+
+    /// Expand a `rows` x `cols` matrix from one seed, as module-LWE needs
+    /// for `A`. Entry (i, j) is `from_seed` under domain `i << 4 | j`, so
+    /// (0, 0) is `from_seed(seed, 0)` and no two entries share a domain.
+    /// Panics if either dimension exceeds 16.
+    pub fn fill_matrix_from_seed(seed: &[u8; 32], rows: usize, cols: usize) -> Vec<Vec<Self>> {
+        assert!(rows <= 16 && cols <= 16, "matrix dimensions must fit a 4-bit domain index");
+        (0..rows)
+            .map(|i| (0..cols).map(|j| Self::from_seed(seed, (i << 4 | j) as u8)).collect())
+            .collect()
+    }
+}
+
+impl RingElement {
+    /// Build from a dynamically-sized buffer; length must be N.
+    /// Coefficients are reduced mod q.
+    pub fn try_from_slice(coeffs: &[u16]) -> Result<Self, ILCError> {
+        if coeffs.len() != N {
+            return Err(ILCError::InvalidInput);
+        }
+        let mut out = Self::default();
+        out.coeffs.copy_from_slice(coeffs);
+        out.reduce();
+        Ok(out)
+    }
+
+    /// Copy coefficients into a `Vec`
+    pub fn to_vec(&self) -> Vec<u16> {
+        self.coeffs.to_vec()
+    }
+
+    /// Widen byte-valued coefficients (always < q)
+    pub fn from_u8_coeffs(bytes: &[u8; N]) -> Self {
+        let mut out = Self::default();
+        for (c, &b) in out.coeffs.iter_mut().zip(bytes) {
+            *c = b as u16;
+        }
+        out
+    }
+
+    /// Narrow to bytes, or None if any coefficient exceeds 255
+    pub fn to_u8_coeffs(&self) -> Option<[u8; N]> {
+        let mut out = [0u8; N];
+        for (b, &c) in out.iter_mut().zip(&self.coeffs) {
+            *b = u8::try_from(c).ok()?;
+        }
+        Some(out)
+    }
+
+    /// Concatenate the coefficients as `COEFF_BITS`-bit fields, LSB first
+    pub fn to_bits(&self) -> BitVec {
+        self.coeffs
+            .iter()
+            .flat_map(|&c| (0..COEFF_BITS).map(move |i| c >> i & 1 == 1))
+            .collect()
+    }
+
+    /// Inverse of `to_bits`; length must be `COEFF_BITS * N`.
+    /// Coefficients are reduced mod q.
+    pub fn from_bits(bits: &[bool]) -> Result<Self, ILCError> {
+        if bits.len() != COEFF_BITS * N {
+            return Err(ILCError::InvalidInput);
+        }
+        let mut out = Self::default();
+        for (c, field) in out.coeffs.iter_mut().zip(bits.chunks(COEFF_BITS)) {
+            *c = field.iter().rev().fold(0, |acc, &b| acc << 1 | b as u16);
+        }
+        out.reduce();
+        Ok(out)
+    }
+
+    /// Kyber's lossy `Compress_q`: each coefficient rounded to
+    /// `round(2^d / q * x) mod 2^d` and packed at `d` bits, LSB first.
+    /// `decompress_coeffs` recovers each coefficient to within
+    /// `ceil(q / 2^(d+1))`. Panics unless 1 <= d <= 11.
+    pub fn compress_coeffs(&self, d: u8) -> Vec<u8> {
+        assert!((1..=11).contains(&d), "d must be in 1..=11");
+        let mask = (1u32 << d) - 1;
+        let mut out = Vec::with_capacity((N * d as usize).div_ceil(8));
+        let (mut acc, mut bits) = (0u32, 0);
+        for &c in &self.coeffs {
+            let x = barrett_reduce(c as u32) as u32;
+            acc |= ((((x << d) + Q / 2) / Q) & mask) << bits;
+            bits += d;
+            while bits >= 8 {
+                out.push(acc as u8);
+                acc >>= 8;
+                bits -= 8;
+            }
+        }
+        if bits > 0 {
+            out.push(acc as u8);
+        }
+        out
+    }
+
+    /// Kyber's `Decompress_q`, inverting `compress_coeffs`: each `d`-bit
+    /// value `y` maps to `round(q / 2^d * y)`. Missing trailing bytes
+    /// read as zero. Panics unless 1 <= d <= 11.
+    pub fn decompress_coeffs(data: &[u8], d: u8) -> Self {
+        assert!((1..=11).contains(&d), "d must be in 1..=11");
+        let mask = (1u32 << d) - 1;
+        let mut out = Self::default();
+        let mut bytes = data.iter();
+        let (mut acc, mut bits) = (0u32, 0);
+        for c in &mut out.coeffs {
+            while bits < d {
+                acc |= (bytes.next().copied().unwrap_or(0) as u32) << bits;
+                bits += 8;
+            }
+            let y = acc & mask;
+            acc >>= d;
+            bits -= d;
+            *c = ((y * Q + (1 << (d - 1))) >> d) as u16;
+        }
+        out
+    }
+
+    /// Centered representatives in (-q/2, q/2]
+    pub fn to_centered(&self) -> [i16; N] {
+        let mut out = [0i16; N];
+        for (o, &c) in out.iter_mut().zip(&self.coeffs) {
+            let c = barrett_reduce(c as u32) as u32;
+            *o = if c > Q / 2 { c as i16 - Q as i16 } else { c as i16 };
+        }
+        out
+    }
+
+    /// Inverse of `to_centered`. Any i16 is accepted and reduced mod q.
+    pub fn from_centered(centered: &[i16; N]) -> Self {
+        let mut out = Self::default();
+        for (o, &c) in out.coeffs.iter_mut().zip(centered) {
+            // 10q > 2^15 lifts every i16 to a non-negative value
+            *o = barrett_reduce((c as i32 + 10 * Q as i32) as u32);
+        }
+        out
+    }
+
+    /// Infinity norm: the largest absolute centered coefficient, so a
+    /// small error polynomial has a small norm rather than one near q
+    pub fn norm_inf(&self) -> u16 {
+        self.to_centered().iter().map(|c| c.unsigned_abs()).max().unwrap_or(0)
+    }
+
+    /// Squared L2 norm of the centered coefficients
+    pub fn norm_l2_sq(&self) -> u64 {
+        self.to_centered().iter().map(|&c| (c as i64 * c as i64) as u64).sum()
+    }
+
+    /// L2 norm of the centered coefficients, for reporting error sizes.
+    /// Needs the `float` feature; use `norm_l2_sq` without it.
+    #[cfg(feature = "float")]
+    pub fn norm_l2(&self) -> f64 {
+        (self.norm_l2_sq() as f64).sqrt()
+    }
+
+    /// Montgomery form: each coefficient scaled by R = 2^16 mod q
+    pub fn to_mont(&self) -> Self {
+        let mut out = Self::default();
+        for (o, &c) in out.coeffs.iter_mut().zip(&self.coeffs) {
+            // c * R^2 fits an i32 for any u16 c, and one reduction
+            // divides out a single R
+            *o = mont_normalize(montgomery_reduce(c as i32 * MONT_R2));
+        }
+        out
+    }
+
+    /// Undo `to_mont`
+    pub fn from_mont(&self) -> Self {
+        let mut out = Self::default();
+        for (o, &c) in out.coeffs.iter_mut().zip(&self.coeffs) {
+            *o = mont_normalize(montgomery_reduce(c as i32));
+        }
+        out
+    }
+
+    /// Add `s` to the constant term, or to every coefficient if `all`
+    pub fn add_scalar(&self, s: u16, all: bool) -> Self {
+        let s = barrett_reduce(s as u32) as u32;
+        let mut result = self.clone();
+        let end = if all { N } else { 1 };
+        for c in &mut result.coeffs[..end] {
+            *c = barrett_reduce(*c as u32 + s);
+        }
+        result
+    }
+
+    /// Subtract `s` from the constant term, or from every coefficient if `all`
+    pub fn sub_scalar(&self, s: u16, all: bool) -> Self {
+        self.add_scalar((Q - barrett_reduce(s as u32) as u32) as u16, all)
+    }
+
+    /// Pointwise product with a position-dependent weight vector, mod q.
+    /// Handy for building structured test inputs.
+    pub fn weight_by_position(&self, weights: &[u16; N]) -> Self {
+        let mut result = self.clone();
+        for (c, &w) in result.coeffs.iter_mut().zip(weights) {
+            // u16 * u16 always fits a u32
+            *c = barrett_reduce(*c as u32 * w as u32);
+        }
+        result
+    }
+
+    /// Reorder coefficients by `bit_reverse_index`, as NTT butterflies
+    /// expect. Applying it twice restores the original order.
+    pub fn bit_reverse(&self) -> Self {
+        let mut out = Self::default();
+        for (i, &c) in self.coeffs.iter().enumerate() {
+            out.coeffs[bit_reverse_index(i)] = c;
+        }
+        out
+    }
+
+    /// Ring automorphism X -> X^k. Valid exponents are the odd k, taken
+    /// mod 2n; panics on even k. Coefficient i moves to X^(ik mod 2n),
+    /// negated when that exponent wraps past n.
+    pub fn apply_automorphism(&self, k: usize) -> Self {
+        assert!(k % 2 == 1, "automorphism exponent must be odd, got {}", k);
+        let mut out = Self::default();
+        for (i, &c) in self.coeffs.iter().enumerate() {
+            let e = (i * (k % (2 * N))) % (2 * N);
+            if e < N {
+                out.coeffs[e] = c;
+            } else {
+                out.coeffs[e - N] = barrett_reduce(Q - barrett_reduce(c as u32) as u32);
+            }
+        }
+        out
+    }
+
+    /// Polynomial multiplication via the NTT, O(n log n). Agrees with
+    /// `mul` for reduced inputs.
+    pub fn mul_ntt(&self, other: &Self) -> Self {
+        self.clone().to_ntt().mul(&other.clone().to_ntt()).from_ntt()
+    }
+
+    /// Fused `self * b + c`: the addition is folded into the X^n + 1
+    /// reduction pass instead of allocating the product first.
+    pub fn mul_add(&self, b: &Self, c: &Self) -> Self {
+        let (lo, hi) = self.schoolbook(b);
+        
+        let mut out = Self::default();
+        for i in 0..N {
+            let (lo, hi) = (barrett_reduce(lo[i]), barrett_reduce(hi[i]));
+            out.coeffs[i] = barrett_reduce(lo as u32 + Q - hi as u32 + c.coeffs[i] as u32);
+        }
+        out
+    }
+
+    /// Polynomial composition `self(g(X)) mod (X^n + 1, q)` via Horner's
+    /// rule. Costs N ring multiplications.
+    pub fn compose(&self, g: &Self) -> Self {
+        let mut result = Self::default();
+        for &c in self.coeffs.iter().rev() {
+            result = result.mul(g).add_scalar(c, false);
+        }
+        result
+    }
+}
+
+/// Reverse the low log2(N) bits of `i` (8 bits for N = 256)
+pub fn bit_reverse_index(i: usize) -> usize {
+    debug_assert!(i < N);
+    i.reverse_bits() >> (usize::BITS - N.trailing_zeros())
+}
+
+/// 2^32 / q, the Barrett constant for `barrett_reduce`
+const BARRETT_M: u64 = (1u64 << 32) / Q as u64;
+
+/// `x mod q` without a division. Accepts any u32: the quotient estimate
+/// `x * floor(2^32 / q) >> 32` undershoots by at most one, so a single
+/// conditional subtraction finishes the job.
+#[inline]
+pub fn barrett_reduce(x: u32) -> u16 {
+    let quot = ((x as u64 * BARRETT_M) >> 32) as u32;
+    csub_q(x - quot * Q)
+}
+
+/// `barrett_reduce` for an arbitrary modulus up to 2^16
+#[inline]
+fn reduce_mod<const MOD: u32>(x: u32) -> u16 {
+    let quot = ((x as u64 * ((1u64 << 32) / MOD as u64)) >> 32) as u32;
+    csub_mod::<MOD>(x - quot * MOD)
+}
+
+/// q^-1 mod 2^16, as a signed value
+const QINV: i16 = -3327;
+
+/// R^2 mod q for R = 2^16, used to enter Montgomery form
+pub const MONT_R2: i32 = 1353;
+
+/// Montgomery reduction: `x * R^-1 mod q` for R = 2^16, in (-q, q).
+/// Requires |x| < q * 2^15, which covers the product of any two values
+/// in (-q, q) and of a u16 with `MONT_R2`.
+#[inline]
+pub fn montgomery_reduce(x: i32) -> i16 {
+    let t = (x as i16).wrapping_mul(QINV);
+    ((x - t as i32 * Q as i32) >> 16) as i16
+}
+
+/// Map a `montgomery_reduce` output in (-q, q) to [0, q)
+#[inline]
+pub fn mont_normalize(x: i16) -> u16 {
+    (x + ((x >> 15) & Q as i16)) as u16
+}
+
+/// Branchless `x - q if x >= q else x`, for x < 2q
+#[inline]
+fn csub_q(x: u32) -> u16 {
+    csub_mod::<Q>(x)
+}
+
+/// Coefficient-wise `add`, written to auto-vectorize
+#[inline(always)]
+fn add_into<const MOD: u32>(out: &mut [u16], a: &[u16], b: &[u16]) {
+    for (o, (&x, &y)) in out.iter_mut().zip(a.iter().zip(b)) {
+        *o = csub_mod::<MOD>(x as u32 + y as u32);
+    }
+}
+
+/// Coefficient-wise `sub`, written to auto-vectorize
+#[inline(always)]
+fn sub_into<const MOD: u32>(out: &mut [u16], a: &[u16], b: &[u16]) {
+    for (o, (&x, &y)) in out.iter_mut().zip(a.iter().zip(b)) {
+        *o = reduce_mod::<MOD>(x as u32 + MOD - y as u32);
+    }
+}
+
+/// The same loops compiled with AVX2 enabled, for CPUs found to have it
+/// at runtime; a baseline x86_64 build otherwise only gets SSE2
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn add_into<const MOD: u32>(out: &mut [u16], a: &[u16], b: &[u16]) {
+        super::add_into::<MOD>(out, a, b)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub(super) unsafe fn sub_into<const MOD: u32>(out: &mut [u16], a: &[u16], b: &[u16]) {
+        super::sub_into::<MOD>(out, a, b)
+    }
+}
+
+/// Whether this CPU has AVX2, checked once
+#[cfg(target_arch = "x86_64")]
+fn has_avx2() -> bool {
+    static HAS_AVX2: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+    *HAS_AVX2.get_or_init(|| is_x86_feature_detected!("avx2"))
+}
+
+/// `add_into` through its AVX2 build when the CPU has it. Elsewhere the
+/// portable loop runs as compiled: on aarch64 that is already NEON,
+/// which the target always has.
+fn add_dispatch<const MOD: u32>(out: &mut [u16], a: &[u16], b: &[u16]) {
+    #[cfg(target_arch = "x86_64")]
+    if has_avx2() {
+        // SAFETY: the CPU was checked for AVX2
+        return unsafe { avx2::add_into::<MOD>(out, a, b) };
+    }
+    add_into::<MOD>(out, a, b)
+}
+
+/// `sub_into` through its AVX2 build when the CPU has it
+fn sub_dispatch<const MOD: u32>(out: &mut [u16], a: &[u16], b: &[u16]) {
+    #[cfg(target_arch = "x86_64")]
+    if has_avx2() {
+        // SAFETY: the CPU was checked for AVX2
+        return unsafe { avx2::sub_into::<MOD>(out, a, b) };
+    }
+    sub_into::<MOD>(out, a, b)
+}
+
+/// `csub_q` for an arbitrary modulus
+#[inline]
+fn csub_mod<const MOD: u32>(x: u32) -> u16 {
+    let r = x.wrapping_sub(MOD);
+    // All ones when the subtraction wrapped, i.e. x < q
+    let mask = 0u32.wrapping_sub(r >> 31);
+    r.wrapping_add(MOD & mask) as u16
+}
+
+/// Histogram of absolute centered errors `|recovered - original|`, binned
+/// evenly over [0, q/2] into `buckets` bins. Counts sum to N.
+///
+/// Panics if `buckets` is zero.
+pub fn error_histogram(original: &RingElement, recovered: &RingElement, buckets: usize) -> Vec<usize> {
+    assert!(buckets > 0, "error_histogram needs at least one bucket");
+    
+    let span = (Q / 2 + 1) as usize;
+    let mut hist = vec![0usize; buckets];
+    for (&o, &r) in original.into_iter().zip(recovered) {
+        let d = barrett_reduce(r as u32 + Q - o as u32) as u32;
+        let err = d.min(Q - d) as usize;
+        hist[err * buckets / span] += 1;
+    }
+    hist
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::marker::PhantomData;
 
     #[test]
     fn test_add_sub_inverse() {
-        let a = RingElement::from_seed(&[1u8; 32], 0);
-        let b = RingElement::from_seed(&[2u8; 32], 0);
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let b = Kyber512Ring::from_seed(&[2u8; 32], 0);
         let sum = a.add(&b);
         let recovered = sum.sub(&b);
         assert_eq!(a, recovered);
     }
 
+    #[test]
+    fn test_add_matches_modular_add() {
+        // Every (x, y) pair in [0, q)^2, N of them per call
+        let ys: Vec<u16> = (0..Q as u16).collect();
+        for x in 0..Q as u16 {
+            let lhs = Kyber512Ring::new([x; N]);
+            for chunk in ys.chunks(N) {
+                let mut rhs = Kyber512Ring::default();
+                rhs.coeffs[..chunk.len()].copy_from_slice(chunk);
+                let sum = lhs.add(&rhs);
+                for (i, &y) in chunk.iter().enumerate() {
+                    assert_eq!(sum.coeffs[i] as u32, (x as u32 + y as u32) % Q);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_generic_small_ring() {
+        type Toy = GenericRingElement<4, 17>;
+        let a = Toy::from_seed(&[1u8; 32], 0);
+        assert!(a.coeffs.iter().all(|&c| c < 17));
+        assert_eq!(a.add(&Toy::default()), a);
+        assert_eq!(a.add(&a).sub(&a), a);
+        
+        // X^3 * X = X^4 = -1
+        let x3 = Toy::new([0, 0, 0, 1]);
+        let x = Toy::new([0, 1, 0, 0]);
+        assert_eq!(x3.mul(&x), Toy::new([16, 0, 0, 0]));
+        // (1 + 2X)(3 + X^3) = 3 + 6X + X^3 + 2X^4 = 1 + 6X + X^3
+        assert_eq!(Toy::new([1, 2, 0, 0]).mul(&Toy::new([3, 0, 0, 1])), Toy::new([1, 6, 0, 1]));
+    }
+
+    #[test]
+    fn test_barrett_matches_modulo() {
+        // Strided sweep over products of reduced values, plus the edges
+        for x in (0..=Q * Q).step_by(7) {
+            assert_eq!(barrett_reduce(x), (x % Q) as u16);
+        }
+        for x in [Q - 1, Q, Q + 1, 2 * Q, (N as u32) * (Q - 1) * (Q - 1), u32::MAX - 1, u32::MAX] {
+            assert_eq!(barrett_reduce(x), (x % Q) as u16);
+        }
+    }
+
+    #[test]
+    fn test_centered_boundaries() {
+        let mut a = Kyber512Ring::default();
+        a.coeffs[..5].copy_from_slice(&[0, 1, Q as u16 / 2, Q as u16 / 2 + 1, Q as u16 - 1]);
+        let centered = a.to_centered();
+        assert_eq!(centered[..5], [0, 1, 1664, -1664, -1]);
+        assert_eq!(Kyber512Ring::from_centered(&centered), a);
+        
+        let b = Kyber512Ring::from_seed(&[4u8; 32], 0);
+        assert_eq!(Kyber512Ring::from_centered(&b.to_centered()), b);
+        
+        let mut extremes = [0i16; N];
+        extremes[0] = i16::MIN;
+        extremes[1] = i16::MAX;
+        let c = Kyber512Ring::from_centered(&extremes);
+        assert_eq!(c.coeffs[0] as i32, (i16::MIN as i32).rem_euclid(Q as i32));
+        assert_eq!(c.coeffs[1] as u32, i16::MAX as u32 % Q);
+    }
+
+    #[test]
+    fn test_bits_roundtrip() {
+        let a = Kyber512Ring::from_seed(&[7u8; 32], 0);
+        let bits = a.to_bits();
+        assert_eq!(bits.len(), 12 * N);
+        // Coefficient 0 occupies the first 12 bits, LSB first
+        assert_eq!(bits[0], a.coeffs[0] & 1 == 1);
+        assert_eq!(bits[11], a.coeffs[0] >> 11 & 1 == 1);
+        assert_eq!(Kyber512Ring::from_bits(&bits).unwrap(), a);
+        assert!(Kyber512Ring::from_bits(&bits[1..]).is_err());
+    }
+
+    #[test]
+    fn test_compress_coeffs_error_bound() {
+        let a = Kyber512Ring::from_seed(&[6u8; 32], 0);
+        for d in [1u8, 4, 10, 11] {
+            let packed = a.compress_coeffs(d);
+            assert_eq!(packed.len(), N * d as usize / 8);
+            
+            let bound = Q.div_ceil(1 << (d + 1)) as u16;
+            let back = Kyber512Ring::decompress_coeffs(&packed, d);
+            assert!(back.sub(&a).norm_inf() <= bound, "d = {d}");
+            
+            // Decompressed values compress back to the same bits
+            assert_eq!(back.compress_coeffs(d), packed);
+        }
+    }
+
+    #[test]
+    fn test_norms() {
+        let zero = Kyber512Ring::default();
+        assert_eq!(zero.norm_inf(), 0);
+        assert_eq!(zero.norm_l2_sq(), 0);
+        
+        // Centered (3, -2, 1, -1664)
+        let mut a = Kyber512Ring::default();
+        a.coeffs[..4].copy_from_slice(&[3, Q as u16 - 2, 1, Q as u16 / 2 + 1]);
+        assert_eq!(a.norm_inf(), 1664);
+        assert_eq!(a.norm_l2_sq(), 9 + 4 + 1 + 1664 * 1664);
+        
+        a.coeffs[3] = 0;
+        assert_eq!(a.norm_inf(), 3);
+        assert_eq!(a.norm_l2_sq(), 14);
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn test_norm_l2() {
+        assert_eq!(Kyber512Ring::default().norm_l2(), 0.0);
+        
+        // Centered (3, -4): a 3-4-5 triangle
+        let mut a = Kyber512Ring::default();
+        a.coeffs[..2].copy_from_slice(&[3, Q as u16 - 4]);
+        assert_eq!(a.norm_l2(), 5.0);
+        
+        a.coeffs.fill(1);
+        assert_eq!(a.norm_l2(), 16.0);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize() {
+        use zeroize::{Zeroize, ZeroizeOnDrop};
+        fn wiped_on_drop<T: ZeroizeOnDrop>(_: &T) {}
+        
+        let mut s = Kyber512Ring::from_seed(&[4u8; 32], 1);
+        assert!(s.coeffs.iter().any(|&c| c != 0));
+        wiped_on_drop(&s);
+        s.zeroize();
+        assert_eq!(s.coeffs, [0; N]);
+    }
+
+    #[cfg(feature = "checked")]
+    #[test]
+    fn test_try_mul_overflow() {
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let b = Kyber512Ring::from_seed(&[2u8; 32], 0);
+        assert_eq!(a.try_mul(&b).unwrap(), a.mul(&b));
+        
+        // 1024 * 3328^2 > 2^32: `mul` wouldn't compile for this ring
+        type Wide = GenericRingElement<1024, 3329>;
+        let top = Wide::new([Q as u16 - 1; 1024]);
+        assert!(matches!(top.try_mul(&top), Err(ILCError::Overflow)));
+        
+        // Small inputs still fit: X^1023 * X = -1
+        let (mut x, mut top_x) = (Wide::default(), Wide::default());
+        x.coeffs[1] = 1;
+        top_x.coeffs[1023] = 1;
+        let mut minus_one = Wide::default();
+        minus_one.coeffs[0] = Q as u16 - 1;
+        assert_eq!(top_x.try_mul(&x).unwrap(), minus_one);
+    }
+
+    #[test]
+    fn test_dispatched_add_sub_match_scalar() {
+        let edges = [0, 1, Q as u16 / 2, Q as u16 - 2, Q as u16 - 1];
+        let mut a = Kyber512Ring::from_seed(&[12u8; 32], 0);
+        let mut b = Kyber512Ring::from_seed(&[13u8; 32], 0);
+        for (i, &x) in edges.iter().enumerate() {
+            for (j, &y) in edges.iter().enumerate() {
+                a.coeffs[5 * i + j] = x;
+                b.coeffs[5 * i + j] = y;
+            }
+        }
+        
+        let (mut add, mut sub) = ([0u16; N], [0u16; N]);
+        add_into::<Q>(&mut add, &a.coeffs, &b.coeffs);
+        sub_into::<Q>(&mut sub, &a.coeffs, &b.coeffs);
+        assert_eq!(a.add(&b).coeffs, add);
+        assert_eq!(a.sub(&b).coeffs, sub);
+        for i in 0..N {
+            assert_eq!(add[i] as u32, (a.coeffs[i] as u32 + b.coeffs[i] as u32) % Q);
+            assert_eq!(sub[i] as u32, (a.coeffs[i] as u32 + Q - b.coeffs[i] as u32) % Q);
+        }
+        
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("avx2") {
+            assert!(has_avx2());
+            let mut out = [0u16; N];
+            unsafe { avx2::add_into::<Q>(&mut out, &a.coeffs, &b.coeffs) };
+            assert_eq!(out, add);
+        }
+    }
+
+    #[test]
+    fn test_montgomery_roundtrip() {
+        let xs: Vec<u16> = (0..Q as u16).collect();
+        for chunk in xs.chunks(N) {
+            let mut a = Kyber512Ring::default();
+            a.coeffs[..chunk.len()].copy_from_slice(chunk);
+            let m = a.to_mont();
+            for (i, &x) in chunk.iter().enumerate() {
+                assert_eq!(m.coeffs[i] as u32, (x as u32) * 65536 % Q);
+            }
+            assert_eq!(m.from_mont(), a);
+        }
+    }
+
+    #[test]
+    fn test_bit_reverse_involution() {
+        assert_eq!(bit_reverse_index(0), 0);
+        assert_eq!(bit_reverse_index(1), 128);
+        assert_eq!(bit_reverse_index(0b0000_0110), 0b0110_0000);
+        assert_eq!(bit_reverse_index(N - 1), N - 1);
+        
+        let a = Kyber512Ring::from_seed(&[4u8; 32], 0);
+        assert_ne!(a.bit_reverse(), a);
+        assert_eq!(a.bit_reverse().bit_reverse(), a);
+    }
+
+    #[test]
+    fn test_automorphism_identity_and_composition() {
+        let a = Kyber512Ring::from_seed(&[6u8; 32], 0);
+        assert_eq!(a.apply_automorphism(1), a);
+        assert_eq!(a.apply_automorphism(2 * N + 1), a);
+        
+        for (j, k) in [(3, 5), (5, 2 * N - 1), (257, 511)] {
+            let composed = a.apply_automorphism(k).apply_automorphism(j);
+            assert_eq!(composed, a.apply_automorphism(j * k % (2 * N)));
+        }
+        
+        // Automorphisms are ring homomorphisms
+        let b = Kyber512Ring::from_seed(&[6u8; 32], 1);
+        assert_eq!(a.mul(&b).apply_automorphism(3), a.apply_automorphism(3).mul(&b.apply_automorphism(3)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_automorphism_even_exponent() {
+        Kyber512Ring::default().apply_automorphism(2);
+    }
+
+    #[test]
+    fn test_into_iterator() {
+        let a = Kyber512Ring::from_seed(&[2u8; 32], 0);
+        let mut sum = 0u32;
+        for c in &a {
+            sum += *c as u32;
+        }
+        assert_eq!(sum, a.coeffs.iter().map(|&c| c as u32).sum::<u32>());
+        assert_eq!((&a).into_iter().count(), N);
+    }
+
     #[test]
     fn test_mul_identity() {
-        let a = RingElement::from_seed(&[1u8; 32], 0);
-        let mut one = RingElement::default();
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let mut one = Kyber512Ring::default();
         one.coeffs[0] = 1;
         let result = a.mul(&one);
         assert_eq!(a, result);
     }
+
+    #[test]
+    fn test_mul_ntt_matches_mul() {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let a = Kyber512Ring::from_seed(&rng.gen(), 0);
+            let b = Kyber512Ring::from_seed(&rng.gen(), 1);
+            assert_eq!(a.mul_ntt(&b), a.mul(&b));
+        }
+    }
+
+    #[test]
+    fn test_mul_add_matches_mul_then_add() {
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let s = Kyber512Ring::from_seed(&[1u8; 32], 1);
+        let e = Kyber512Ring::from_seed(&[1u8; 32], 2);
+        assert_eq!(a.mul_add(&s, &e), a.mul(&s).add(&e));
+    }
+
+    #[test]
+    fn test_slice_roundtrip() {
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let recovered = Kyber512Ring::try_from_slice(&a.to_vec()).unwrap();
+        assert_eq!(a, recovered);
+
+        // Unreduced input is brought into [0, q)
+        let mut raw = vec![0u16; N];
+        raw[0] = Q as u16 + 5;
+        assert_eq!(Kyber512Ring::try_from_slice(&raw).unwrap().coeffs[0], 5);
+    }
+
+    #[test]
+    fn test_slice_wrong_length() {
+        assert!(Kyber512Ring::try_from_slice(&[0u16; N - 1]).is_err());
+        assert!(Kyber512Ring::try_from_slice(&vec![0u16; N + 1]).is_err());
+    }
+
+    #[test]
+    fn test_add_scalar_constant_term() {
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let shifted = a.add_scalar(5, false);
+        assert_eq!(shifted.coeffs[0], ((a.coeffs[0] as u32 + 5) % Q) as u16);
+        assert_eq!(shifted.coeffs[1..], a.coeffs[1..]);
+        assert_eq!(shifted.sub_scalar(5, false), a);
+    }
+
+    #[test]
+    fn test_add_scalar_all() {
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let shifted = a.add_scalar(Q as u16 - 1, true);
+        for i in 0..N {
+            assert_eq!(shifted.coeffs[i], ((a.coeffs[i] as u32 + Q - 1) % Q) as u16);
+        }
+        assert_eq!(shifted.sub_scalar(Q as u16 - 1, true), a);
+    }
+
+    #[test]
+    fn test_weight_by_position() {
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let mut weights = [1u16; N];
+        weights[7] = 0;
+        weights[8] = 2;
+        let w = a.weight_by_position(&weights);
+        assert_eq!(w.coeffs[7], 0);
+        assert_eq!(w.coeffs[8], ((a.coeffs[8] as u32 * 2) % Q) as u16);
+        assert_eq!(w.coeffs[9], a.coeffs[9]);
+    }
+
+    #[test]
+    fn test_compose_with_identity() {
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let mut x = Kyber512Ring::default();
+        x.coeffs[1] = 1;
+        assert_eq!(a.compose(&x), a);
+    }
+
+    #[test]
+    fn test_u8_coeffs_roundtrip() {
+        let mut bytes = [0u8; N];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = (i * 7) as u8;
+        }
+        let poly = Kyber512Ring::from_u8_coeffs(&bytes);
+        assert_eq!(poly.to_u8_coeffs(), Some(bytes));
+
+        let wide = poly.add_scalar(256, false);
+        assert_eq!(wide.to_u8_coeffs(), None);
+    }
+
+    #[test]
+    fn test_error_histogram_lossy() {
+        // Drop the low 3 bits: errors land in [0, 7]
+        let a = Kyber512Ring::from_seed(&[1u8; 32], 0);
+        let mut lossy = a.clone();
+        for c in &mut lossy.coeffs {
+            *c &= !7;
+        }
+
+        let coarse = error_histogram(&a, &lossy, 4);
+        assert_eq!(coarse.iter().sum::<usize>(), N);
+        assert_eq!(coarse[0], N);
+
+        // One bucket per error value
+        let fine = error_histogram(&a, &lossy, (Q / 2 + 1) as usize);
+        assert_eq!(fine.iter().sum::<usize>(), N);
+        assert_eq!(fine[..8].iter().sum::<usize>(), N);
+        assert_eq!(error_histogram(&a, &a, 4), vec![N, 0, 0, 0]);
+    }
+
+    /// Pearson correlation between the centered coefficients of two
+    /// equally long lists of polynomials
+    fn correlation(x: &[Kyber512Ring], y: &[Kyber512Ring]) -> f64 {
+        let samples = |polys: &[Kyber512Ring]| -> Vec<f64> {
+            polys.iter().flat_map(|p| p.to_centered()).map(f64::from).collect()
+        };
+        let (x, y) = (samples(x), samples(y));
+        let n = x.len() as f64;
+        let (mx, my) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+        let mut cov = 0.0;
+        let mut vx = 0.0;
+        let mut vy = 0.0;
+        for (a, b) in x.iter().zip(&y) {
+            cov += (a - mx) * (b - my);
+            vx += (a - mx) * (a - mx);
+            vy += (b - my) * (b - my);
+        }
+        cov / (vx * vy).sqrt()
+    }
+
+    #[test]
+    fn test_from_seed_domain_separation() {
+        // 64 seeds * N coefficients: independent outputs give |r| ~ 0.008
+        let seeds: Vec<[u8; 32]> = (0..64u8).map(|i| [i; 32]).collect();
+        let domain = |d: u8| -> Vec<Kyber512Ring> {
+            seeds.iter().map(|s| Kyber512Ring::from_seed(s, d)).collect()
+        };
+        let (d0, d1, d2) = (domain(0), domain(1), domain(2));
+        
+        for (x, y) in [(&d0, &d1), (&d0, &d2), (&d1, &d2)] {
+            let r = correlation(x, y);
+            assert!(r.abs() < 0.05, "domain outputs correlated: r = {}", r);
+        }
+        // Sanity check on the statistic itself
+        assert!((correlation(&d0, &d0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let elem = RingElement::from_seed(&[4u8; 32], 0);
+
+        let json = serde_json::to_string(&elem).unwrap();
+        assert!(json.starts_with('['));
+        assert_eq!(serde_json::from_str::<RingElement>(&json).unwrap(), elem);
+        let bytes = bincode::serialize(&elem).unwrap();
+        assert_eq!(bincode::deserialize::<RingElement>(&bytes).unwrap(), elem);
+
+        let key = crate::types::RingLWEKey { a: elem.clone(), b: RingElement::from_seed(&[4u8; 32], 1) };
+        let decoded: crate::types::RingLWEKey = serde_json::from_str(&serde_json::to_string(&key).unwrap()).unwrap();
+        assert_eq!((decoded.a, decoded.b), (key.a, key.b));
+
+        // Wrong length, and a coefficient of q
+        assert!(serde_json::from_str::<RingElement>("[1,2,3]").is_err());
+        let mut coeffs = elem.coeffs.to_vec();
+        coeffs[0] = Q as u16;
+        assert!(serde_json::from_str::<RingElement>(&serde_json::to_string(&coeffs).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_fill_matrix_from_seed() {
+        let seed = [17u8; 32];
+        let m = RingElement::fill_matrix_from_seed(&seed, 3, 2);
+        assert_eq!((m.len(), m[0].len()), (3, 2));
+        assert_eq!(m[0][0], RingElement::from_seed(&seed, 0));
+
+        let entries: Vec<&RingElement> = m.iter().flatten().collect();
+        for (i, x) in entries.iter().enumerate() {
+            assert!(entries[i + 1..].iter().all(|y| y != x));
+        }
+        // (i, j) and (j, i) use different domains
+        assert_ne!(m[0][1], m[1][0]);
+    }
+
+    /// Arithmetic identities every parameter set must satisfy
+    fn check_ring<const DEG: usize, const MOD: u32>(_: PhantomData<GenericRingElement<DEG, MOD>>) {
+        let [a, b, c] = [0, 1, 2].map(|d| GenericRingElement::<DEG, MOD>::from_seed(&[d + 7; 32], d));
+        assert!(a.coeffs.iter().all(|&x| (x as u32) < MOD));
+        
+        let zero = GenericRingElement::<DEG, MOD>::default();
+        let mut one = zero.clone();
+        one.coeffs[0] = 1;
+        assert_eq!(a.add(&zero), a);
+        assert_eq!(a.mul(&one), a);
+        assert_eq!(a.add(&b).sub(&b), a);
+        assert_eq!(a.sub(&a), zero);
+        assert_eq!(a.add(&b), b.add(&a));
+        assert_eq!(a.mul(&b), b.mul(&a));
+        assert_eq!(a.mul(&b.add(&c)), a.mul(&b).add(&a.mul(&c)));
+        assert_eq!(a.mul(&b).mul(&c), a.mul(&b.mul(&c)));
+        
+        // X * X^(n-1) = X^n = -1
+        let (mut x, mut top) = (zero.clone(), zero.clone());
+        x.coeffs[1] = 1;
+        top.coeffs[DEG - 1] = 1;
+        let mut minus_one = zero.clone();
+        minus_one.coeffs[0] = (MOD - 1) as u16;
+        assert_eq!(x.mul(&top), minus_one);
+        
+        // Unreduced representatives reduce back
+        let mut shifted = GenericRingElement::<DEG, MOD>::new(a.coeffs.map(|x| x + MOD as u16));
+        shifted.reduce();
+        assert_eq!(shifted, a);
+    }
+
+    /// Sketch roundtrip and norms, for sets at the sketcher's (N, Q)
+    fn sketched(_: PhantomData<RingElement>) {
+        use crate::types::{AlgebraicShield, RingLWEKey};
+        
+        let seed = [5u8; 32];
+        let key = RingLWEKey { a: RingElement::from_seed(&seed, 0), b: RingElement::from_seed(&seed, 1) };
+        let recovered = RingLWEKey::decompress(&key.compress(seed)).unwrap();
+        assert_eq!(recovered.b, key.b);
+        assert_eq!(recovered.a, key.a);
+        
+        let e = RingElement::from_centered(&[-1; N]);
+        assert_eq!(e.norm_inf(), 1);
+        assert_eq!(e.norm_l2_sq(), N as u64);
+        assert_eq!(key.b.sub(&key.b).norm_inf(), 0);
+    }
+
+    /// One test per parameter set, running `check_ring` and, when named,
+    /// the extra checks valid for that set
+    macro_rules! param_set_tests {
+        ($($name:ident: $ring:ty $(, $extra:ident)*;)*) => {$(
+            #[test]
+            fn $name() {
+                check_ring(PhantomData::<$ring>);
+                $($extra(PhantomData::<$ring>);)*
+            }
+        )*};
+    }
+
+    param_set_tests! {
+        param_set_kyber512: Kyber512Ring, sketched;
+        param_set_kyber768: Kyber768Ring, sketched;
+        param_set_kyber1024: Kyber1024Ring, sketched;
+        param_set_toy_4_17: GenericRingElement<4, 17>;
+    }
 }
@@ -5,109 +5,2212 @@
 //! 2. Store parity sums for adjacent pairs to enable reconstruction
 //! 3. Include checksum for verification
 
-use crate::ring::{RingElement, N, Q};
-use crate::types::{RingLWEKey, CompressedPK, AlgebraicShield, ILCError};
+use crate::ring::{barrett_reduce, RingElement, COEFF_BITS, N, Q};
+use crate::types::{RingLWEKey, ModuleLWEKey, CompressedPK, AlgebraicShield, DynStrategy, ILCError, ParityMode, ChecksumKind, SeedRef, CompressionMetrics};
+use crate::types::{CorrelatedSketch, CorrelatedEntry, InterleavedPK, BalancedPK, NarrowParityPK, AlgebraicPK, SecretErrorPK, StrategyId, PatternedPK, CompressedDelta};
+#[cfg(feature = "float")]
+use crate::types::CompressionLevel;
 use sha3::{Sha3_256, Digest};
+use std::time::Instant;
 
 /// Compute checksum of polynomial coefficients
 fn compute_checksum(poly: &RingElement) -> [u8; 8] {
+    compute_module_checksum(std::slice::from_ref(poly))
+}
+
+/// Checksum over several polynomials in order; for one polynomial it
+/// equals `compute_checksum`
+fn compute_module_checksum(polys: &[RingElement]) -> [u8; 8] {
+    let mut state = ChecksumState::Sha3(Box::default());
+    for &c in polys.iter().flatten() {
+        state.update(c);
+    }
+    state.finish()
+}
+
+/// `kind` checksum over several polynomials in order. `InvalidInput` for
+/// the keyed and chained kinds, which need more than the polynomials.
+fn compute_checksum_as(kind: ChecksumKind, polys: &[RingElement]) -> Result<[u8; 8], ILCError> {
+    let mut state = ChecksumState::new(kind)?;
+    for &c in polys.iter().flatten() {
+        state.update(c);
+    }
+    Ok(state.finish())
+}
+
+/// Check `sketch.checksum` against the reconstructed polynomials, with
+/// the algorithm the sketch names. MAC and chained sketches fail with
+/// `InvalidInput`: they only verify through their own decoders.
+fn verify_checksum(sketch: &CompressedPK, polys: &[RingElement]) -> Result<(), ILCError> {
+    if !ct_eq(&compute_checksum_as(sketch.checksum_kind, polys)?, &sketch.checksum) {
+        return Err(ILCError::ChecksumMismatch);
+    }
+    Ok(())
+}
+
+/// Incremental checksum of coefficients, fed one at a time
+enum ChecksumState {
+    Sha3(Box<Sha3_256>),
+    Crc32(u32),
+    None,
+}
+
+impl ChecksumState {
+    fn new(kind: ChecksumKind) -> Result<Self, ILCError> {
+        match kind {
+            ChecksumKind::Sha3 => Ok(ChecksumState::Sha3(Box::default())),
+            ChecksumKind::Crc32 => Ok(ChecksumState::Crc32(!0)),
+            ChecksumKind::None => Ok(ChecksumState::None),
+            ChecksumKind::Mac | ChecksumKind::Chained => Err(ILCError::InvalidInput),
+        }
+    }
+
+    fn update(&mut self, c: u16) {
+        match self {
+            ChecksumState::Sha3(hasher) => hasher.update(c.to_le_bytes()),
+            ChecksumState::Crc32(crc) => *crc = crc32_update(*crc, &c.to_le_bytes()),
+            ChecksumState::None => {}
+        }
+    }
+
+    fn finish(self) -> [u8; 8] {
+        match self {
+            ChecksumState::Sha3(hasher) => finish_checksum(*hasher),
+            ChecksumState::Crc32(crc) => {
+                let mut checksum = [0u8; 8];
+                checksum[..4].copy_from_slice(&(!crc).to_le_bytes());
+                checksum
+            }
+            ChecksumState::None => [0u8; 8],
+        }
+    }
+}
+
+/// Table for the reflected CRC-32 polynomial 0xEDB88320
+const CRC32_TABLE: [u32; 256] = {
+    let mut t = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        t[i] = crc;
+        i += 1;
+    }
+    t
+};
+
+/// Feed `bytes` into a running CRC-32 (pre- and post-inversion are left
+/// to the caller)
+fn crc32_update(mut crc: u32, bytes: &[u8]) -> u32 {
+    for &b in bytes {
+        crc = CRC32_TABLE[((crc ^ b as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    crc
+}
+
+/// Checksum of `poly` chained onto the previous sketch's checksum, so a
+/// log of sketches forms a hash chain
+fn compute_chained_checksum(poly: &RingElement, prev_checksum: &[u8; 8]) -> [u8; 8] {
     let mut hasher = Sha3_256::new();
-    for c in &poly.coeffs {
+    hasher.update(prev_checksum);
+    for c in poly {
         hasher.update(c.to_le_bytes());
     }
-    let hash = hasher.finalize();
-    let mut checksum = [0u8; 8];
-    checksum.copy_from_slice(&hash[..8]);
-    checksum
-}
+    finish_checksum(hasher)
+}
+
+/// Truncate a finished coefficient hash to the checksum width
+fn finish_checksum(hasher: Sha3_256) -> [u8; 8] {
+    let hash = hasher.finalize();
+    let mut checksum = [0u8; 8];
+    checksum.copy_from_slice(&hash[..8]);
+    checksum
+}
+
+/// HMAC-SHA3-256 of polynomial coefficients under `key`, truncated to the
+/// checksum width
+fn compute_mac(poly: &RingElement, key: &[u8]) -> [u8; 8] {
+    // SHA3-256 rate in bytes, used as the HMAC block size
+    const BLOCK: usize = 136;
+    
+    let mut padded = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        padded[..32].copy_from_slice(&Sha3_256::digest(key));
+    } else {
+        padded[..key.len()].copy_from_slice(key);
+    }
+    
+    let mut inner = Sha3_256::new();
+    inner.update(padded.map(|k| k ^ 0x36));
+    for c in poly {
+        inner.update(c.to_le_bytes());
+    }
+    
+    let mut outer = Sha3_256::new();
+    outer.update(padded.map(|k| k ^ 0x5c));
+    outer.update(inner.finalize());
+    let hash = outer.finalize();
+    
+    let mut tag = [0u8; 8];
+    tag.copy_from_slice(&hash[..8]);
+    tag
+}
+
+/// Compare checksums and tags without early exit. Every verification
+/// goes through here, so a mismatch doesn't leak how many leading bytes
+/// matched.
+fn ct_eq(a: &[u8; 8], b: &[u8; 8]) -> bool {
+    #[cfg(feature = "constant-time")]
+    {
+        use subtle::ConstantTimeEq;
+        a[..].ct_eq(&b[..]).into()
+    }
+    #[cfg(not(feature = "constant-time"))]
+    {
+        a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+    }
+}
+
+/// Rebuild `b` from the anchors and parity, without verification
+fn reconstruct_b(sketch: &CompressedPK) -> Result<RingElement, ILCError> {
+    check_layout(sketch)?;
+    if !sketch.has_parity {
+        return RingElement::try_from_slice(&sketch.anchor_coeffs);
+    }
+    
+    Ok(join_pairs(&sketch.anchor_coeffs, &sketch.parity, sketch.parity_mode))
+}
+
+/// Split `b` into anchors (even coefficients) and per-pair parity
+fn split_pairs(b: &RingElement, parity_mode: ParityMode) -> (Vec<u16>, Vec<u16>) {
+    // Extract anchor coefficients (even indices)
+    let anchor_coeffs: Vec<u16> = b.coeffs
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| i % 2 == 0)
+        .map(|(_, &c)| c)
+        .collect();
+    
+    // Compute parity: relation of adjacent pairs mod q
+    // This allows reconstruction: if we know anchor[i] and parity[i],
+    // we can recover odd[i] (e.g. parity[i] - anchor[i] mod q for Sum)
+    let parity: Vec<u16> = (0..N/2)
+        .map(|i| parity_mode.encode(b.coeffs[2*i], b.coeffs[2*i + 1]))
+        .collect();
+    
+    (anchor_coeffs, parity)
+}
+
+/// Inverse of `split_pairs`; both slices must hold N/2 entries
+fn join_pairs(anchor_coeffs: &[u16], parity: &[u16], parity_mode: ParityMode) -> RingElement {
+    let mut b = RingElement::default();
+    
+    for i in 0..N/2 {
+        let anchor = anchor_coeffs[i];
+        
+        // Even coefficient is the anchor
+        b.coeffs[2*i] = anchor;
+        
+        // Odd coefficient: undo the parity relation
+        b.coeffs[2*i + 1] = parity_mode.recover_odd(anchor, parity[i]);
+    }
+    
+    b
+}
+
+/// Reject sketches that aren't of a single polynomial or whose vector
+/// lengths don't match their `has_parity` layout
+fn check_layout(sketch: &CompressedPK) -> Result<(), ILCError> {
+    if sketch.rank != 1 {
+        return Err(ILCError::InvalidInput);
+    }
+    check_module_layout(sketch)
+}
+
+/// `check_layout` for a sketch of `rank` polynomials
+fn check_module_layout(sketch: &CompressedPK) -> Result<(), ILCError> {
+    let k = sketch.rank as usize;
+    let expected = if sketch.has_parity { (k * N/2, k * N/2) } else { (k * N, 0) };
+    if k == 0 || k > ModuleLWEKey::MAX_RANK || sketch.decimation != 2 || (sketch.anchor_coeffs.len(), sketch.parity.len()) != expected {
+        return Err(ILCError::InvalidInput);
+    }
+    Ok(())
+}
+
+/// Centered representative of `x - y mod q`, in (-q/2, q/2]
+fn centered_diff(x: u16, y: u16) -> i32 {
+    let d = ((x as u32 % Q + Q - y as u32 % Q) % Q) as i32;
+    if d > (Q / 2) as i32 { d - Q as i32 } else { d }
+}
+
+/// Largest |e| coefficient searched by `decompress_recover_missing`
+pub const RECOVERY_ERROR_BOUND: i32 = 8;
+
+/// Most lost anchors `decompress_recover_missing` will search for; the
+/// search tries up to (2 * RECOVERY_ERROR_BOUND + 1)^missing combinations
+pub const RECOVERY_MAX_MISSING: usize = 4;
+
+/// Largest |coefficient| of `s` and `e` that `compress_algebraic` packs,
+/// and the error norm `decompress_algebraic` accepts
+pub const ALGEBRAIC_ERROR_BOUND: u16 = 7;
+
+/// Pack centered values into signed nibbles, two per byte, or None if
+/// any exceeds `ALGEBRAIC_ERROR_BOUND`
+fn pack_nibbles(values: &[i16]) -> Option<Vec<u8>> {
+    let nibble = |v: i16| (v.unsigned_abs() <= ALGEBRAIC_ERROR_BOUND).then_some(v as u8 & 0x0f);
+    values
+        .chunks(2)
+        .map(|pair| Some(nibble(pair[0])? | nibble(pair[1])? << 4))
+        .collect()
+}
+
+/// Inverse of `pack_nibbles`
+fn unpack_nibbles(bytes: &[u8]) -> Vec<i16> {
+    // Move each nibble to the top of an i8 and shift back to sign-extend
+    bytes.iter().flat_map(|&b| [b << 4, b & 0xf0].map(|n| (n as i8 >> 4) as i16)).collect()
+}
+
+/// Decompress `b` coefficient by coefficient, handing each `(index, value)`
+/// to `sink` in index order without materializing the polynomial.
+///
+/// The checksum is computed incrementally and checked only at the end, so
+/// on `ChecksumMismatch` the consumer must discard what it received.
+pub fn decompress_streaming(
+    sketch: &CompressedPK,
+    mut sink: impl FnMut(usize, u16),
+) -> Result<(), ILCError> {
+    check_layout(sketch)?;
+    
+    let mut state = ChecksumState::new(sketch.checksum_kind)?;
+    if sketch.has_parity {
+        for i in 0..N/2 {
+            let even = sketch.anchor_coeffs[i];
+            let odd = sketch.parity_mode.recover_odd(even, sketch.parity[i]);
+            
+            sink(2*i, even);
+            state.update(even);
+            sink(2*i + 1, odd);
+            state.update(odd);
+        }
+    } else {
+        for (i, &c) in sketch.anchor_coeffs.iter().enumerate() {
+            let c = (c as u32 % Q) as u16;
+            sink(i, c);
+            state.update(c);
+        }
+    }
+    
+    if !ct_eq(&state.finish(), &sketch.checksum) {
+        return Err(ILCError::ChecksumMismatch);
+    }
+    Ok(())
+}
+
+/// Pair order for `compress_shuffled`: position `j` on the wire holds pair
+/// `perm[j]`. Derived from `shuffle_key` by a domain-separated hash
+/// seeding ChaCha20, so nothing about it is stored.
+fn shuffle_permutation(shuffle_key: &[u8; 32]) -> Vec<usize> {
+    use rand::SeedableRng;
+    use rand::seq::SliceRandom;
+    use rand_chacha::ChaCha20Rng;
+    
+    let mut hasher = Sha3_256::new();
+    hasher.update(b"ilc-shuffle");
+    hasher.update(shuffle_key);
+    let mut rng_seed = [0u8; 32];
+    rng_seed.copy_from_slice(&hasher.finalize());
+    
+    let mut perm: Vec<usize> = (0..N/2).collect();
+    perm.shuffle(&mut ChaCha20Rng::from_seed(rng_seed));
+    perm
+}
+
+/// Depth-first search for `decompress_with_recovery`. Wire values are
+/// numbered anchors first, then parity; each level flips one bit in a
+/// value numbered `from` or later, so no combination is visited twice.
+/// Fails as soon as a second match turns up.
+fn search_bit_flips(
+    sketch: &CompressedPK,
+    b: &mut RingElement,
+    from: usize,
+    budget: usize,
+    fix: &mut Option<RingElement>,
+) -> Result<(), ILCError> {
+    if budget == 0 {
+        return Ok(());
+    }
+    let mode = sketch.parity_mode;
+    for slot in from..N {
+        let i = slot % (N/2);
+        let (even, odd) = (b.coeffs[2*i], b.coeffs[2*i + 1]);
+        // The received parity, not one re-encoded from `b`: a flip may
+        // have pushed it to q or above, where re-encoding would reduce it
+        let parity = sketch.parity[i];
+        for bit in 0..COEFF_BITS {
+            let (anchor, parity) = if slot < N/2 {
+                (even ^ (1 << bit), parity)
+            } else {
+                (even, parity ^ (1 << bit))
+            };
+            // Only the flipped value must be in range; the other one may be
+            // the corrupted value, left for a deeper level to fix
+            let flipped = if slot < N/2 { anchor } else { parity };
+            if flipped as u32 >= Q {
+                continue;
+            }
+            b.coeffs[2*i] = anchor;
+            b.coeffs[2*i + 1] = mode.recover_odd(anchor, parity);
+            if verify_checksum(sketch, std::slice::from_ref(b)).is_ok() {
+                if fix.is_some() {
+                    return Err(ILCError::ReconstructionFailed);
+                }
+                *fix = Some(b.clone());
+            }
+            search_bit_flips(sketch, b, slot + 1, budget - 1, fix)?;
+        }
+        b.coeffs[2*i] = even;
+        b.coeffs[2*i + 1] = odd;
+    }
+    Ok(())
+}
+
+/// Order-sensitive combination of per-part checksums, for transports that
+/// split a key across several parts: SHA3-256 over the part count and the
+/// checksums in order, truncated to the checksum width
+pub fn combine_checksums(parts: &[[u8; 8]]) -> [u8; 8] {
+    let mut hasher = Sha3_256::new();
+    hasher.update((parts.len() as u64).to_le_bytes());
+    for part in parts {
+        hasher.update(part);
+    }
+    finish_checksum(hasher)
+}
+
+/// Check reassembled parts, in transmission order, against a value from
+/// `combine_checksums`
+pub fn verify_combined(parts: &[RingElement], combined: &[u8; 8]) -> Result<(), ILCError> {
+    let checksums: Vec<[u8; 8]> = parts.iter().map(compute_checksum).collect();
+    if !ct_eq(&combine_checksums(&checksums), combined) {
+        return Err(ILCError::ChecksumMismatch);
+    }
+    Ok(())
+}
+
+/// Walk an append-only log of sketches from `compress_chained`, oldest
+/// first, starting from `genesis`. Changing any sketch, even with its own
+/// checksum recomputed, breaks every link after it.
+pub fn verify_chain(sketches: &[CompressedPK], genesis: [u8; 8]) -> Result<(), ILCError> {
+    let mut prev = genesis;
+    for sketch in sketches {
+        RingLWEKey::decompress_chained(sketch, prev)?;
+        prev = sketch.checksum;
+    }
+    Ok(())
+}
+
+/// Pick the most faithful `CompressionLevel` whose total wire size over
+/// `samples` is at most `target_ratio` of the uncompressed keys, counting
+/// only levels under which every sample decodes to within `max_error` of
+/// its `b` (the `norm_inf` of the difference; `Lossless` is always
+/// exact). Falls back to the most aggressive level that qualifies when
+/// none meets the target, and to `Lossless` for an empty sample. The
+/// decimated levels only qualify for smooth `b`; a uniformly random one
+/// stays `Lossless` under any useful bound.
+#[cfg(feature = "float")]
+pub fn autotune(samples: &[RingLWEKey], target_ratio: f64, max_error: u16) -> CompressionLevel {
+    let seed = [0u8; 32];
+    let key_bytes: usize = samples.iter().map(RingLWEKey::size_bytes).sum();
+    if key_bytes == 0 {
+        return CompressionLevel::Lossless;
+    }
+
+    let mut best = CompressionLevel::Lossless;
+    for level in CompressionLevel::ALL {
+        let mut wire_bytes = 0;
+        let decodes = samples.iter().all(|key| {
+            let sketch = key.compress_with_decimation(seed, level.decimation());
+            wire_bytes += crate::to_bytes_unchecked(&sketch).len();
+            match RingLWEKey::decompress_decimated(&sketch) {
+                Ok(recovered) => recovered.b.sub(&key.b).norm_inf() <= max_error,
+                Err(_) => false,
+            }
+        });
+        if !decodes {
+            continue;
+        }
+        best = level;
+        if wire_bytes as f64 / key_bytes as f64 <= target_ratio {
+            break;
+        }
+    }
+    best
+}
+
+/// Decompress `b` straight to centered representatives in (-q/2, q/2].
+/// The checksum is verified on the canonical coefficients first, and 'a'
+/// is never regenerated.
+pub fn decompress_centered(sketch: &CompressedPK) -> Result<[i16; N], ILCError> {
+    let b = reconstruct_b(sketch)?;
+    verify_checksum(sketch, std::slice::from_ref(&b))?;
+    Ok(b.to_centered())
+}
+
+/// Compress several `b` polynomials that share one seed for 'a' (e.g.
+/// multiple users of the same public matrix). The seed is stored once and
+/// every `b` after the first is stored as `b_k - b_0 mod q`, packed at
+/// the few bits its centered values need when the keys are correlated.
+/// Checksums cover the actual `b_k`.
+pub fn compress_correlated(bs: &[RingElement], seed: [u8; 32]) -> CorrelatedSketch {
+    let entries = bs
+        .iter()
+        .enumerate()
+        .map(|(k, b)| {
+            let coded = if k == 0 { b.clone() } else { b.sub(&bs[0]) };
+            CorrelatedEntry::pack(&coded, compute_checksum(b))
+        })
+        .collect();
+    
+    CorrelatedSketch { seed: SeedRef::Inline(seed), a_domain: 0, entries }
+}
+
+/// Decompress a `CorrelatedSketch` into one key per entry, all sharing 'a'
+pub fn decompress_correlated(sketch: &CorrelatedSketch) -> Result<Vec<RingLWEKey>, ILCError> {
+    let coded: Vec<RingElement> = sketch.entries.iter().map(CorrelatedEntry::unpack).collect::<Result<_, _>>()?;
+    
+    let mut bs: Vec<RingElement> = Vec::with_capacity(sketch.entries.len());
+    for (coded, entry) in coded.into_iter().zip(&sketch.entries) {
+        let b = match bs.first() {
+            Some(first) => coded.add(first),
+            None => coded,
+        };
+        if !ct_eq(&compute_checksum(&b), &entry.checksum) {
+            return Err(ILCError::ChecksumMismatch);
+        }
+        bs.push(b);
+    }
+    
+    let seed = sketch.seed.resolve(|_| None)?;
+    let a = RingElement::from_seed(&seed, sketch.a_domain);
+    Ok(bs.into_iter().map(|b| RingLWEKey { a: a.clone(), b }).collect())
+}
+
+impl ParityMode {
+    /// Parity value stored for an (even, odd) pair
+    pub(crate) fn encode(self, even: u16, odd: u16) -> u16 {
+        // Received values are only known to fit 12 bits, so reduce first
+        let (even, odd) = (even as u32 % Q, odd as u32 % Q);
+        match self {
+            ParityMode::Sum => ((even + odd) % Q) as u16,
+            ParityMode::Difference => ((odd + Q - even) % Q) as u16,
+        }
+    }
+
+    /// Recover the odd coefficient from its anchor and stored parity
+    pub(crate) fn recover_odd(self, anchor: u16, parity: u16) -> u16 {
+        let (anchor, parity) = (anchor as u32 % Q, parity as u32 % Q);
+        match self {
+            ParityMode::Sum => ((parity + Q - anchor) % Q) as u16,
+            ParityMode::Difference => ((parity + anchor) % Q) as u16,
+        }
+    }
+}
+
+impl DynStrategy for ParityMode {
+    fn compress_dyn(&self, key: &RingLWEKey, seed: [u8; 32]) -> Result<CompressedPK, ILCError> {
+        Ok(key.compress_with_parity_mode(seed, *self))
+    }
+}
+
+impl AlgebraicShield for RingLWEKey {
+    /// Compress the public key using algebraic sketching
+    fn compress(&self, seed: [u8; 32]) -> CompressedPK {
+        self.compress_with_parity_mode(seed, ParityMode::Sum)
+    }
+    
+    /// Decompress and reconstruct the public key
+    fn decompress(sketch: &CompressedPK) -> Result<Self, ILCError> {
+        Self::decompress_with_registry(sketch, |_| None)
+    }
+}
+
+impl AlgebraicShield for ModuleLWEKey {
+    /// Sketch each component of `t` in turn, with `seed` (normally
+    /// `self.a_seed`) standing in for `A`.
+    ///
+    /// # Panics
+    ///
+    /// If the rank is 0 or above `ModuleLWEKey::MAX_RANK`.
+    fn compress(&self, seed: [u8; 32]) -> CompressedPK {
+        assert!(
+            (1..=ModuleLWEKey::MAX_RANK).contains(&self.rank()),
+            "module rank must be between 1 and MAX_RANK"
+        );
+        let mut anchor_coeffs = Vec::with_capacity(self.rank() * N/2);
+        let mut parity = Vec::with_capacity(self.rank() * N/2);
+        for poly in &self.t {
+            let (anchors, pairs) = split_pairs(poly, ParityMode::Sum);
+            anchor_coeffs.extend(anchors);
+            parity.extend(pairs);
+        }
+        
+        CompressedPK {
+            seed: SeedRef::Inline(seed),
+            a_domain: 0,
+            rank: self.rank() as u8,
+            decimation: 2,
+            anchor_coeffs,
+            checksum: compute_module_checksum(&self.t),
+            checksum_kind: ChecksumKind::Sha3,
+            parity,
+            has_parity: true,
+            parity_mode: ParityMode::Sum,
+            signature: None,
+            #[cfg(feature = "seq")]
+            seq: 0,
+        }
+    }
+    
+    /// Reconstruct every component of `t` and check the joint checksum.
+    /// `A` is not carried: `matrix_a` regenerates it from the seed.
+    fn decompress(sketch: &CompressedPK) -> Result<Self, ILCError> {
+        check_module_layout(sketch)?;
+        let t: Vec<RingElement> = if sketch.has_parity {
+            sketch.anchor_coeffs
+                .chunks(N/2)
+                .zip(sketch.parity.chunks(N/2))
+                .map(|(anchors, parity)| join_pairs(anchors, parity, sketch.parity_mode))
+                .collect()
+        } else {
+            sketch.anchor_coeffs
+                .chunks(N)
+                .map(RingElement::try_from_slice)
+                .collect::<Result<_, _>>()?
+        };
+        
+        verify_checksum(sketch, &t)?;
+        
+        let a_seed = sketch.seed.resolve(|_| None)?;
+        Ok(ModuleLWEKey { a_seed, t })
+    }
+}
+
+impl RingLWEKey {
+    /// Decompress, resolving `SeedRef::Id` seeds through `registry`
+    pub fn decompress_with_registry(
+        sketch: &CompressedPK,
+        registry: impl Fn(u32) -> Option<[u8; 32]>,
+    ) -> Result<Self, ILCError> {
+        // Reconstruct 'b' from anchors and parity (cheap length checks
+        // first, so malformed sketches never pay for regenerating 'a')
+        let b = reconstruct_b(sketch)?;
+        
+        // Verify checksum
+        verify_checksum(sketch, std::slice::from_ref(&b))?;
+        
+        // Regenerate 'a' from seed
+        let seed = sketch.seed.resolve(registry)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// Compress, storing parity under the given relation
+    pub fn compress_with_parity_mode(&self, seed: [u8; 32], parity_mode: ParityMode) -> CompressedPK {
+        self.sketch_with_checksum(seed, parity_mode, compute_checksum(&self.b))
+    }
+
+    /// Compress and report `CompressionMetrics` to `metrics`, for feeding
+    /// a monitoring pipeline without tying the crate to one
+    pub fn compress_with_metrics(
+        &self,
+        seed: [u8; 32],
+        metrics: &mut dyn FnMut(CompressionMetrics),
+    ) -> CompressedPK {
+        let start = Instant::now();
+        let checksum = compute_checksum(&self.b);
+        let checksum_time = start.elapsed();
+        let sketch = self.sketch_with_checksum(seed, ParityMode::default(), checksum);
+        let wire_bytes = crate::to_bytes_unchecked(&sketch).len();
+        let key_bytes = self.size_bytes();
+        metrics(CompressionMetrics {
+            #[cfg(feature = "float")]
+            ratio: wire_bytes as f64 / key_bytes as f64,
+            wire_bytes,
+            key_bytes,
+            checksum_time,
+        });
+        sketch
+    }
+
+    /// Compression body around a checksum the caller computed, so that
+    /// only `compress_with_metrics` reads the clock (`Instant::now` panics
+    /// on wasm32-unknown-unknown)
+    fn sketch_with_checksum(&self, seed: [u8; 32], parity_mode: ParityMode, checksum: [u8; 8]) -> CompressedPK {
+        let (anchor_coeffs, parity) = split_pairs(&self.b, parity_mode);
+        CompressedPK {
+            seed: SeedRef::Inline(seed),
+            a_domain: 0,
+            rank: 1,
+            decimation: 2,
+            anchor_coeffs,
+            checksum,
+            checksum_kind: ChecksumKind::Sha3,
+            parity,
+            has_parity: true,
+            parity_mode,
+            signature: None,
+            #[cfg(feature = "seq")]
+            seq: 0,
+        }
+    }
+
+    /// Keep only every `k`-th coefficient of `b`. `k = 2` is plain
+    /// `compress`, lossless through its parity. For larger `k` the sketch
+    /// carries the N/k anchors alone and is lossy: `decompress_decimated`
+    /// interpolates the rest, which is only close for smooth `b`. Panics
+    /// unless `k >= 2` divides N.
+    pub fn compress_with_decimation(&self, seed: [u8; 32], k: usize) -> CompressedPK {
+        assert!(k >= 2 && N.is_multiple_of(k), "decimation must be at least 2 and divide N");
+        let mut sketch = self.compress(seed);
+        if k > 2 {
+            sketch.decimation = k as u16;
+            sketch.anchor_coeffs = self.b.coeffs.iter().step_by(k).copied().collect();
+            sketch.parity.clear();
+            sketch.has_parity = false;
+        }
+        sketch
+    }
+
+    /// Decode any `decimation`. For 2 this is `decompress`. Otherwise each
+    /// gap is filled by linear interpolation between neighbouring anchors
+    /// in centered form (the last gap towards `-b[0]`, as X^n = -1), and
+    /// since the result is approximate the checksum is not checked.
+    pub fn decompress_decimated(sketch: &CompressedPK) -> Result<Self, ILCError> {
+        let k = sketch.decimation as usize;
+        if k == 2 {
+            return Self::decompress(sketch);
+        }
+        if k < 2 || !N.is_multiple_of(k) || sketch.rank != 1 || sketch.anchor_coeffs.len() != N / k {
+            return Err(ILCError::InvalidInput);
+        }
+        
+        let mut b = RingElement::default();
+        for (j, &x) in sketch.anchor_coeffs.iter().enumerate() {
+            let next = match sketch.anchor_coeffs.get(j + 1) {
+                Some(&y) => y,
+                None => barrett_reduce(Q - barrett_reduce(sketch.anchor_coeffs[0] as u32) as u32),
+            };
+            let step = centered_diff(next, x);
+            for t in 0..k {
+                let offset = (step * t as i32 + (k as i32 / 2) * step.signum()) / k as i32;
+                b.coeffs[j * k + t] = (x as i32 + offset).rem_euclid(Q as i32) as u16;
+            }
+        }
+        
+        let seed = sketch.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// `compress`, but first check that `self.a` is what the receiver will
+    /// regenerate from `seed`, failing with `InvalidInput` if not.
+    /// `compress` itself only carries the seed, so a foreign 'a' would be
+    /// silently swapped out on decompression.
+    pub fn compress_checked(&self, seed: [u8; 32]) -> Result<CompressedPK, ILCError> {
+        if self.a != RingElement::from_seed(&seed, 0) {
+            return Err(ILCError::InvalidInput);
+        }
+        Ok(self.compress(seed))
+    }
+
+    /// Compress without parity: the anchors carry all N coefficients and
+    /// `parity` is left intentionally empty
+    pub fn compress_anchors_only(&self, seed: [u8; 32]) -> CompressedPK {
+        let mut sketch = self.compress(seed);
+        sketch.anchor_coeffs = self.b.to_vec();
+        sketch.parity.clear();
+        sketch.has_parity = false;
+        sketch
+    }
+
+    /// Compress with the checksum computed by `kind` rather than SHA3;
+    /// `decompress` follows the kind stored in the sketch. Fails with
+    /// `InvalidInput` for `Mac` and `Chained`, which need a key or a
+    /// previous checksum: use `compress_with_mac` and `compress_chained`.
+    pub fn compress_with_checksum(&self, seed: [u8; 32], kind: ChecksumKind) -> Result<CompressedPK, ILCError> {
+        let mut sketch = self.compress(seed);
+        sketch.checksum = compute_checksum_as(kind, std::slice::from_ref(&self.b))?;
+        sketch.checksum_kind = kind;
+        Ok(sketch)
+    }
+
+    /// Compress with a keyed MAC (HMAC-SHA3-256, truncated) in place of
+    /// the plain checksum, for integrity against active tampering when
+    /// sender and receiver share `mac_key`. Such sketches only verify via
+    /// `decompress_verify_mac`.
+    pub fn compress_with_mac(&self, seed: [u8; 32], mac_key: &[u8]) -> CompressedPK {
+        let mut sketch = self.compress(seed);
+        sketch.checksum = compute_mac(&self.b, mac_key);
+        sketch.checksum_kind = ChecksumKind::Mac;
+        sketch
+    }
+
+    /// Compress with the checksum chained onto `prev_checksum`, the
+    /// checksum of the previous sketch in an append-only log (any fixed
+    /// value for the first). Such sketches only verify via
+    /// `decompress_chained` or `verify_chain`.
+    pub fn compress_chained(&self, seed: [u8; 32], prev_checksum: [u8; 8]) -> CompressedPK {
+        let mut sketch = self.compress(seed);
+        sketch.checksum = compute_chained_checksum(&self.b, &prev_checksum);
+        sketch.checksum_kind = ChecksumKind::Chained;
+        sketch
+    }
+
+    /// Compress keeping the coefficients marked in `keep` as anchors.
+    ///
+    /// Every other coefficient is stored as parity against the coefficient
+    /// just before it, so runs of any length are recovered by chaining from
+    /// the anchor that starts them. The pattern must therefore mark
+    /// coefficient 0; otherwise it fails with `InvalidInput`.
+    pub fn compress_with_pattern(&self, seed: [u8; 32], keep: &[bool; N]) -> Result<PatternedPK, ILCError> {
+        if !keep[0] {
+            return Err(ILCError::InvalidInput);
+        }
+        
+        let parity_mode = ParityMode::default();
+        let mut keep_mask = [0u8; N / 8];
+        let mut anchor_coeffs = Vec::new();
+        let mut parity = Vec::new();
+        for i in 0..N {
+            if keep[i] {
+                keep_mask[i / 8] |= 1 << (i % 8);
+                anchor_coeffs.push(self.b.coeffs[i]);
+            } else {
+                parity.push(parity_mode.encode(self.b.coeffs[i - 1], self.b.coeffs[i]));
+            }
+        }
+        
+        Ok(PatternedPK {
+            seed: SeedRef::Inline(seed),
+            a_domain: 0,
+            keep_mask,
+            anchor_coeffs,
+            parity,
+            checksum: compute_checksum(&self.b),
+            parity_mode,
+        })
+    }
+
+    /// Decompress a `PatternedPK`
+    pub fn decompress_patterned(sketch: &PatternedPK) -> Result<Self, ILCError> {
+        let anchors = (0..N).filter(|&i| sketch.is_anchor(i)).count();
+        if !sketch.is_anchor(0) || sketch.anchor_coeffs.len() != anchors || sketch.parity.len() != N - anchors {
+            return Err(ILCError::InvalidInput);
+        }
+        
+        let mut b = RingElement::default();
+        let mut anchor_coeffs = sketch.anchor_coeffs.iter();
+        let mut parity = sketch.parity.iter();
+        for i in 0..N {
+            // Lengths were checked against the mask, so neither runs out
+            b.coeffs[i] = if sketch.is_anchor(i) {
+                *anchor_coeffs.next().unwrap()
+            } else {
+                sketch.parity_mode.recover_odd(b.coeffs[i - 1], *parity.next().unwrap())
+            };
+        }
+        
+        if !ct_eq(&compute_checksum(&b), &sketch.checksum) {
+            return Err(ILCError::ChecksumMismatch);
+        }
+        
+        let seed = sketch.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// Encode this key as the coefficients that changed since `previous`,
+    /// for incremental key updates to a receiver that still holds it
+    pub fn compress_delta_from(&self, previous: &RingLWEKey, seed: [u8; 32]) -> CompressedDelta {
+        let changes = self.b.sub(&previous.b).coeffs
+            .iter()
+            .enumerate()
+            .filter(|(_, &d)| d != 0)
+            .map(|(i, &d)| (i as u16, d))
+            .collect();
+        
+        CompressedDelta {
+            seed: SeedRef::Inline(seed),
+            a_domain: 0,
+            changes,
+            checksum: compute_checksum(&self.b),
+        }
+    }
+
+    /// Apply a `CompressedDelta` to `previous`. Fails with
+    /// `ChecksumMismatch` if `previous` isn't the key the delta was made
+    /// against.
+    pub fn decompress_delta(delta: &CompressedDelta, previous: &RingLWEKey) -> Result<Self, ILCError> {
+        let mut b = previous.b.clone();
+        for &(i, d) in &delta.changes {
+            let c = b.coeffs.get_mut(i as usize).ok_or(ILCError::InvalidInput)?;
+            *c = ((*c as u32 + d as u32) % Q) as u16;
+        }
+        
+        if !ct_eq(&compute_checksum(&b), &delta.checksum) {
+            return Err(ILCError::ChecksumMismatch);
+        }
+        
+        let seed = delta.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, delta.a_domain);
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// Compress with the (anchor, parity) pairs reordered by a permutation
+    /// derived from `shuffle_key`, so the wire order leaks nothing about
+    /// coefficient positions to parties without the key. Such sketches only
+    /// decode via `decompress_shuffled`.
+    pub fn compress_shuffled(&self, seed: [u8; 32], shuffle_key: &[u8; 32]) -> CompressedPK {
+        let mut sketch = self.compress(seed);
+        let perm = shuffle_permutation(shuffle_key);
+        sketch.anchor_coeffs = perm.iter().map(|&p| sketch.anchor_coeffs[p]).collect();
+        sketch.parity = perm.iter().map(|&p| sketch.parity[p]).collect();
+        sketch
+    }
+
+    /// Decompress a sketch from `compress_shuffled`. A wrong `shuffle_key`
+    /// puts pairs in the wrong places and yields `ChecksumMismatch`.
+    pub fn decompress_shuffled(sketch: &CompressedPK, shuffle_key: &[u8; 32]) -> Result<Self, ILCError> {
+        check_layout(sketch)?;
+        if !sketch.has_parity {
+            return Err(ILCError::InvalidInput);
+        }
+        
+        let mut unshuffled = sketch.clone();
+        for (j, &p) in shuffle_permutation(shuffle_key).iter().enumerate() {
+            unshuffled.anchor_coeffs[p] = sketch.anchor_coeffs[j];
+            unshuffled.parity[p] = sketch.parity[j];
+        }
+        Self::decompress(&unshuffled)
+    }
+
+    /// Compress with parity in balanced form; see `BalancedPK`
+    pub fn compress_balanced(&self, seed: [u8; 32]) -> BalancedPK {
+        self.compress(seed).to_balanced().expect("compress always emits parity")
+    }
+
+    /// Compress with parity bit-packed as narrowly as this key allows; see
+    /// `NarrowParityPK`. Both parity modes are tried and the narrower kept,
+    /// `Sum` on a tie. Never larger than the 12-bit packing of `compress`.
+    pub fn compress_parity_opt(&self, seed: [u8; 32]) -> NarrowParityPK {
+        [ParityMode::Sum, ParityMode::Difference]
+            .map(|mode| {
+                self.compress_with_parity_mode(seed, mode)
+                    .to_balanced()
+                    .expect("compress always emits parity")
+                    .to_narrow()
+            })
+            .into_iter()
+            .min_by_key(|sketch| sketch.parity_bits)
+            .expect("two candidates")
+    }
+
+    /// Decompress a `NarrowParityPK`
+    pub fn decompress_parity_opt(sketch: &NarrowParityPK) -> Result<Self, ILCError> {
+        Self::decompress_balanced(&sketch.to_balanced()?)
+    }
+
+    /// Try each decoder in `chain` in order and return the first key that
+    /// passes its checksum, for fleets where senders disagree on the
+    /// sketch flavour. Fails with the last decoder's error, or
+    /// `InvalidInput` for an empty chain.
+    pub fn decompress_with_fallback(sketch: &CompressedPK, chain: &[StrategyId]) -> Result<Self, ILCError> {
+        let mut last = ILCError::InvalidInput;
+        for strategy in chain {
+            let result = match strategy {
+                StrategyId::Standard => Self::decompress(sketch),
+                StrategyId::SumParity => Self::decompress(&CompressedPK { parity_mode: ParityMode::Sum, ..sketch.clone() }),
+                StrategyId::DifferenceParity => {
+                    Self::decompress(&CompressedPK { parity_mode: ParityMode::Difference, ..sketch.clone() })
+                }
+                StrategyId::Repair => Self::decompress_repair(sketch),
+            };
+            match result {
+                Ok(key) => return Ok(key),
+                Err(e) => last = e,
+            }
+        }
+        Err(last)
+    }
+
+    /// Decompress a `BalancedPK`
+    pub fn decompress_balanced(sketch: &BalancedPK) -> Result<Self, ILCError> {
+        Self::decompress(&sketch.to_standard())
+    }
+
+    /// Decompress an `InterleavedPK`, reading anchor and parity together
+    pub fn decompress_interleaved(sketch: &InterleavedPK) -> Result<Self, ILCError> {
+        if sketch.pairs.len() != N/2 {
+            return Err(ILCError::InvalidInput);
+        }
+        
+        let mut b = RingElement::default();
+        for (i, &(anchor, parity)) in sketch.pairs.iter().enumerate() {
+            b.coeffs[2*i] = anchor;
+            b.coeffs[2*i + 1] = sketch.parity_mode.recover_odd(anchor, parity);
+        }
+        
+        if !ct_eq(&compute_checksum(&b), &sketch.checksum) {
+            return Err(ILCError::ChecksumMismatch);
+        }
+        
+        let seed = sketch.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// Decompress a sketch from `compress_with_mac`, checking the tag in
+    /// constant time. A wrong key or tampered sketch yields
+    /// `ChecksumMismatch`.
+    pub fn decompress_verify_mac(sketch: &CompressedPK, mac_key: &[u8]) -> Result<Self, ILCError> {
+        if sketch.checksum_kind != ChecksumKind::Mac {
+            return Err(ILCError::InvalidInput);
+        }
+        let b = reconstruct_b(sketch)?;
+        
+        if !ct_eq(&compute_mac(&b, mac_key), &sketch.checksum) {
+            return Err(ILCError::ChecksumMismatch);
+        }
+        
+        let seed = sketch.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// Decompress a sketch from `compress_chained`, given the checksum of
+    /// the sketch before it
+    pub fn decompress_chained(sketch: &CompressedPK, prev_checksum: [u8; 8]) -> Result<Self, ILCError> {
+        if sketch.checksum_kind != ChecksumKind::Chained {
+            return Err(ILCError::InvalidInput);
+        }
+        let b = reconstruct_b(sketch)?;
+        
+        if !ct_eq(&compute_chained_checksum(&b, &prev_checksum), &sketch.checksum) {
+            return Err(ILCError::ChecksumMismatch);
+        }
+        
+        let seed = sketch.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// Decompress, repairing a single anchor corrupted by one bit flip;
+    /// `decompress_with_recovery` with one error.
+    pub fn decompress_repair(sketch: &CompressedPK) -> Result<Self, ILCError> {
+        Self::decompress_with_recovery(sketch, 1)
+    }
+
+    /// Decompress, correcting up to `max_errors` bit flips in transit.
+    ///
+    /// On `ChecksumMismatch`, every combination of up to `max_errors`
+    /// single-bit flips in distinct 12-bit wire values is tried. A flipped
+    /// anchor re-derives its odd coefficient from the parity, a flipped
+    /// parity value re-derives only the odd coefficient, and the checksum
+    /// is rechecked. The result is returned only if exactly one candidate
+    /// matches; none or several yield `ReconstructionFailed`. Each error
+    /// allowed multiplies the search by about N * 12 checksum evaluations,
+    /// so anything past 2 is impractical.
+    pub fn decompress_with_recovery(sketch: &CompressedPK, max_errors: usize) -> Result<Self, ILCError> {
+        match Self::decompress(sketch) {
+            Err(ILCError::ChecksumMismatch) if sketch.has_parity => {}
+            result => return result,
+        }
+        
+        let mut b = reconstruct_b(sketch)?;
+        let mut fix = None;
+        search_bit_flips(sketch, &mut b, 0, max_errors, &mut fix)?;
+        
+        let b = fix.ok_or(ILCError::ReconstructionFailed)?;
+        let seed = sketch.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// Compress by dropping the odd coefficients of `b` altogether, to be
+    /// rebuilt from `b = a*s + e` by `decompress_algebraic`. `secret` is
+    /// `s`; it and `e` must be small (every centered coefficient within
+    /// `ALGEBRAIC_ERROR_BOUND`), otherwise this fails with `InvalidInput`.
+    ///
+    /// The result embeds `s` itself: never publish it as a public key.
+    pub fn compress_algebraic(&self, seed: [u8; 32], secret: &RingElement) -> Result<AlgebraicPK, ILCError> {
+        let e = self.b.sub(&self.a.mul(secret));
+        if e.norm_inf() > ALGEBRAIC_ERROR_BOUND {
+            return Err(ILCError::InvalidInput);
+        }
+        let odd: Vec<i16> = e.to_centered().iter().skip(1).step_by(2).copied().collect();
+        
+        Ok(AlgebraicPK {
+            seed: SeedRef::Inline(seed),
+            a_domain: 0,
+            anchor_coeffs: self.b.coeffs.iter().step_by(2).copied().collect(),
+            secret: pack_nibbles(&secret.to_centered()).ok_or(ILCError::InvalidInput)?,
+            odd_errors: pack_nibbles(&odd).ok_or(ILCError::InvalidInput)?,
+            checksum: compute_checksum(&self.b),
+        })
+    }
+
+    /// Rebuild `b` from an `AlgebraicPK`: the odd coefficients are
+    /// `(a*s)_i + e_i`. Fails with `ReconstructionFailed` when the implied
+    /// error `b - a*s` exceeds `ALGEBRAIC_ERROR_BOUND` anywhere, which
+    /// means the anchors don't fit the carried secret.
+    pub fn decompress_algebraic(sketch: &AlgebraicPK) -> Result<Self, ILCError> {
+        if sketch.anchor_coeffs.len() != N/2 || sketch.secret.len() != N/2 || sketch.odd_errors.len() != N/4 {
+            return Err(ILCError::InvalidInput);
+        }
+        
+        let seed = sketch.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        let s: [i16; N] = unpack_nibbles(&sketch.secret).try_into().map_err(|_| ILCError::InvalidInput)?;
+        let predicted = a.mul(&RingElement::from_centered(&s));
+        
+        let mut b = RingElement::default();
+        for (i, e) in unpack_nibbles(&sketch.odd_errors).into_iter().enumerate() {
+            b.coeffs[2*i] = sketch.anchor_coeffs[i];
+            b.coeffs[2*i + 1] = barrett_reduce((predicted.coeffs[2*i + 1] as i32 + e as i32 + Q as i32) as u32);
+        }
+        
+        if b.sub(&predicted).norm_inf() > ALGEBRAIC_ERROR_BOUND {
+            return Err(ILCError::ReconstructionFailed);
+        }
+        if !ct_eq(&compute_checksum(&b), &sketch.checksum) {
+            return Err(ILCError::ChecksumMismatch);
+        }
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// Highest-compression mode, for receivers that already know the
+    /// secret (e.g. backup recovery): only the error `e = b - a*s` is
+    /// kept, with neither anchors nor parity. Fails with `InvalidInput`
+    /// unless every centered coefficient of `e` is within
+    /// `ALGEBRAIC_ERROR_BOUND`.
+    pub fn compress_with_secret(&self, seed: [u8; 32], s: &RingElement) -> Result<SecretErrorPK, ILCError> {
+        let e = self.b.sub(&self.a.mul(s));
+        Ok(SecretErrorPK {
+            seed: SeedRef::Inline(seed),
+            a_domain: 0,
+            errors: pack_nibbles(&e.to_centered()).ok_or(ILCError::InvalidInput)?,
+            checksum: compute_checksum(&self.b),
+        })
+    }
+
+    /// Rebuild `b = a*s + e` from a `SecretErrorPK`; the checksum catches
+    /// a wrong `s`
+    pub fn decompress_with_secret(sketch: &SecretErrorPK, s: &RingElement) -> Result<Self, ILCError> {
+        if sketch.errors.len() != N/2 {
+            return Err(ILCError::InvalidInput);
+        }
+        let seed = sketch.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        let e: [i16; N] = unpack_nibbles(&sketch.errors).try_into().map_err(|_| ILCError::InvalidInput)?;
+        let b = a.mul(s).add(&RingElement::from_centered(&e));
+
+        if !ct_eq(&compute_checksum(&b), &sketch.checksum) {
+            return Err(ILCError::ChecksumMismatch);
+        }
+        Ok(RingLWEKey { a, b })
+    }
+
+    /// Best-effort decompression when the last `missing` anchors were lost
+    /// in transit but all parity survived.
+    ///
+    /// Uses the RLWE relation `b = a*s + e` with the known secret `s`: each
+    /// lost anchor is searched among the values whose implied error on both
+    /// coefficients of the pair stays within `RECOVERY_ERROR_BOUND`, and the
+    /// checksum picks the right combination. Only works when `e` is small,
+    /// and the search grows as (2*bound + 1)^missing, so more than
+    /// `RECOVERY_MAX_MISSING` lost anchors is `InvalidInput`.
+    pub fn decompress_recover_missing(
+        sketch: &CompressedPK,
+        s: &RingElement,
+        missing: usize,
+    ) -> Result<Self, ILCError> {
+        // With no checksum there is nothing to pick the right combination
+        if !sketch.has_parity || sketch.parity.len() != N/2 || missing > RECOVERY_MAX_MISSING
+            || sketch.anchor_coeffs.len().checked_add(missing) != Some(N/2)
+            || sketch.checksum_kind == ChecksumKind::None
+        {
+            return Err(ILCError::InvalidInput);
+        }
+        
+        let seed = sketch.seed.resolve(|_| None)?;
+        let a = RingElement::from_seed(&seed, sketch.a_domain);
+        let predicted = a.mul(s);
+        let known = sketch.anchor_coeffs.len();
+        
+        let mut b = RingElement::default();
+        let mode = sketch.parity_mode;
+        for i in 0..known {
+            let anchor = sketch.anchor_coeffs[i];
+            b.coeffs[2*i] = anchor;
+            b.coeffs[2*i + 1] = mode.recover_odd(anchor, sketch.parity[i]);
+        }
+        
+        // Candidate anchors per lost group: both implied errors must be small
+        let candidates: Vec<Vec<u16>> = (known..N/2)
+            .map(|i| {
+                (-RECOVERY_ERROR_BOUND..=RECOVERY_ERROR_BOUND)
+                    .map(|d| (predicted.coeffs[2*i] as i32 + d).rem_euclid(Q as i32) as u16)
+                    .filter(|&anchor| {
+                        let odd = mode.recover_odd(anchor, sketch.parity[i]);
+                        centered_diff(odd, predicted.coeffs[2*i + 1]).abs() <= RECOVERY_ERROR_BOUND
+                    })
+                    .collect()
+            })
+            .collect();
+        
+        if candidates.iter().any(|c| c.is_empty()) {
+            return Err(ILCError::ReconstructionFailed);
+        }
+        
+        // Walk every combination until the checksum matches
+        let mut choice = vec![0usize; missing];
+        loop {
+            for (k, &c) in choice.iter().enumerate() {
+                let i = known + k;
+                let anchor = candidates[k][c];
+                b.coeffs[2*i] = anchor;
+                b.coeffs[2*i + 1] = mode.recover_odd(anchor, sketch.parity[i]);
+            }
+            
+            match verify_checksum(sketch, std::slice::from_ref(&b)) {
+                Ok(()) => return Ok(RingLWEKey { a, b }),
+                Err(ILCError::ChecksumMismatch) => {}
+                Err(e) => return Err(e),
+            }
+            
+            let mut k = 0;
+            loop {
+                if k == missing {
+                    return Err(ILCError::ReconstructionFailed);
+                }
+                choice[k] += 1;
+                if choice[k] < candidates[k].len() {
+                    break;
+                }
+                choice[k] = 0;
+                k += 1;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::SizeClass;
+    use rand::Rng;
+
+    fn random_key() -> (RingLWEKey, [u8; 32]) {
+        let seed = rand::thread_rng().gen::<[u8; 32]>();
+        let a = RingElement::from_seed(&seed, 0);
+        let s = RingElement::from_seed(&seed, 1); // secret
+        let e = RingElement::from_seed(&seed, 2); // error (small in practice)
+        let b = a.mul(&s).add(&e);
+        
+        (RingLWEKey { a, b }, seed)
+    }
+
+    #[test]
+    fn test_compress_decompress_roundtrip() {
+        let (key, seed) = random_key();
+        let compressed = key.compress(seed);
+        let recovered = RingLWEKey::decompress(&compressed).unwrap();
+        
+        assert_eq!(key.b.coeffs, recovered.b.coeffs);
+    }
+
+    #[test]
+    fn test_parallel_roundtrips() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<RingLWEKey>();
+        assert_send_sync::<CompressedPK>();
+        
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..8)
+                .map(|_| scope.spawn(|| {
+                    for _ in 0..8 {
+                        let (key, seed) = random_key();
+                        let sketch = key.compress(seed);
+                        let recovered = RingLWEKey::decompress(&sketch).unwrap();
+                        assert_eq!(recovered.b, key.b);
+                        // Same seed, same 'a', whichever thread expands it
+                        assert_eq!(RingElement::from_seed(&seed, 0), key.a);
+                    }
+                }))
+                .collect();
+            for worker in workers {
+                worker.join().unwrap();
+            }
+        });
+    }
+
+    #[test]
+    fn test_roundtrip_preserves_a() {
+        let (key, seed) = random_key();
+        
+        let recovered = [
+            RingLWEKey::decompress(&key.compress(seed)).unwrap(),
+            RingLWEKey::decompress(&key.compress_with_parity_mode(seed, ParityMode::Difference)).unwrap(),
+            RingLWEKey::decompress(&crate::from_bytes(&crate::to_bytes(&key.compress(seed)).unwrap()).unwrap()).unwrap(),
+            RingLWEKey::decompress_verify_mac(&key.compress_with_mac(seed, b"k"), b"k").unwrap(),
+            RingLWEKey::decompress_interleaved(&key.compress(seed).to_interleaved().unwrap()).unwrap(),
+        ];
+        for r in &recovered {
+            assert_eq!(key.a.coeffs, r.a.coeffs);
+        }
+    }
+
+    #[test]
+    fn test_recover_missing_anchors() {
+        let seed = rand::thread_rng().gen::<[u8; 32]>();
+        let a = RingElement::from_seed(&seed, 0);
+        let s = RingElement::from_seed(&seed, 1);
+        
+        // Small error in [-2, 2]
+        let mut rng = rand::thread_rng();
+        let mut e = RingElement::default();
+        for c in &mut e.coeffs {
+            *c = ((rng.gen_range(0..5u32) + Q - 2) % Q) as u16;
+        }
+        let key = RingLWEKey { a: a.clone(), b: a.mul(&s).add(&e) };
+        
+        let mut compressed = key.compress(seed);
+        compressed.anchor_coeffs.truncate(N/2 - 2);
+        
+        let recovered = RingLWEKey::decompress_recover_missing(&compressed, &s, 2).unwrap();
+        assert_eq!(key.b.coeffs, recovered.b.coeffs);
+        
+        // The sketch's own checksum kind picks the combination
+        let mut crc = key.compress_with_checksum(seed, ChecksumKind::Crc32).unwrap();
+        crc.anchor_coeffs.truncate(N/2 - 2);
+        assert_eq!(RingLWEKey::decompress_recover_missing(&crc, &s, 2).unwrap().b, key.b);
+        let mut unchecked = key.compress_with_checksum(seed, ChecksumKind::None).unwrap();
+        unchecked.anchor_coeffs.truncate(N/2 - 2);
+        assert!(matches!(RingLWEKey::decompress_recover_missing(&unchecked, &s, 2), Err(ILCError::InvalidInput)));
+        
+        // Too many lost anchors, or a count that would overflow
+        let mut gutted = key.compress(seed);
+        gutted.anchor_coeffs.truncate(N/2 - RECOVERY_MAX_MISSING - 1);
+        assert!(matches!(
+            RingLWEKey::decompress_recover_missing(&gutted, &s, RECOVERY_MAX_MISSING + 1),
+            Err(ILCError::InvalidInput)
+        ));
+        assert!(matches!(RingLWEKey::decompress_recover_missing(&compressed, &s, usize::MAX), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_secret_roundtrip() {
+        let seed = rand::thread_rng().gen::<[u8; 32]>();
+        let a = RingElement::from_seed(&seed, 0);
+
+        // Secret and error in [-3, 3]
+        let mut rng = rand::thread_rng();
+        let mut small = || {
+            let mut p = RingElement::default();
+            for c in &mut p.coeffs {
+                *c = ((rng.gen_range(0..7u32) + Q - 3) % Q) as u16;
+            }
+            p
+        };
+        let (s, e) = (small(), small());
+        let key = RingLWEKey { a: a.clone(), b: a.mul(&s).add(&e) };
+
+        let compressed = key.compress_with_secret(seed, &s).unwrap();
+        assert!(compressed.coeff_bytes() < key.compress_algebraic(seed, &s).unwrap().coeff_bytes());
+
+        let recovered = RingLWEKey::decompress_with_secret(&compressed, &s).unwrap();
+        assert_eq!(recovered.b, key.b);
+        assert_eq!(recovered.a, key.a);
+
+        // Wrong secret, and a key whose error is too large to pack
+        let wrong = s.add(&RingElement::from_centered(&[1; N]));
+        assert!(matches!(RingLWEKey::decompress_with_secret(&compressed, &wrong), Err(ILCError::ChecksumMismatch)));
+        let (key, seed) = random_key();
+        assert!(matches!(key.compress_with_secret(seed, &s), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_algebraic_roundtrip() {
+        let seed = rand::thread_rng().gen::<[u8; 32]>();
+        let a = RingElement::from_seed(&seed, 0);
+        
+        // Secret and error in [-2, 2]
+        let mut rng = rand::thread_rng();
+        let mut small = || {
+            let mut p = RingElement::default();
+            for c in &mut p.coeffs {
+                *c = ((rng.gen_range(0..5u32) + Q - 2) % Q) as u16;
+            }
+            p
+        };
+        let (s, e) = (small(), small());
+        let key = RingLWEKey { a: a.clone(), b: a.mul(&s).add(&e) };
+        
+        let compressed = key.compress_algebraic(seed, &s).unwrap();
+        // Anchors, odd errors and the secret together cost exactly the
+        // 12-bit packing of all of b: no saving
+        assert_eq!(compressed.coeff_bytes(), key.compress(seed).coeff_bytes());
+        
+        let recovered = RingLWEKey::decompress_algebraic(&compressed).unwrap();
+        assert_eq!(recovered.b, key.b);
+        assert_eq!(recovered.a, key.a);
+        
+        // Anchors that don't fit the secret
+        let mut tampered = compressed.clone();
+        tampered.anchor_coeffs[3] = (tampered.anchor_coeffs[3] + Q as u16 / 2) % Q as u16;
+        assert!(matches!(RingLWEKey::decompress_algebraic(&tampered), Err(ILCError::ReconstructionFailed)));
+        
+        // A uniformly random key has no small error for this secret
+        let (uniform, seed) = random_key();
+        assert!(matches!(uniform.compress_algebraic(seed, &s), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_pair_reconstruction_exhaustive() {
+        // Decimation codes each (even, odd) pair independently, so checking
+        // every pair in [0, q)^2 proves the roundtrip lossless for any b.
+        // Pairs go through split_pairs/join_pairs N/2 at a time.
+        let pairs: Vec<(u16, u16)> = (0..Q as u16)
+            .flat_map(|even| (0..Q as u16).map(move |odd| (even, odd)))
+            .collect();
+        for mode in [ParityMode::Sum, ParityMode::Difference] {
+            for chunk in pairs.chunks(N/2) {
+                let mut b = RingElement::default();
+                for (i, &(even, odd)) in chunk.iter().enumerate() {
+                    b.coeffs[2*i] = even;
+                    b.coeffs[2*i + 1] = odd;
+                }
+                let (anchors, parity) = split_pairs(&b, mode);
+                assert_eq!(join_pairs(&anchors, &parity, mode), b, "{:?}", mode);
+            }
+        }
+    }
+
+    #[test]
+    fn test_repair_single_corrupted_anchor() {
+        let (key, seed) = random_key();
+        let compressed = key.compress(seed);
+        
+        let mut corrupted = compressed.clone();
+        corrupted.anchor_coeffs[17] ^= 1 << 3;
+        assert!(matches!(RingLWEKey::decompress(&corrupted), Err(ILCError::ChecksumMismatch)));
+        assert_eq!(RingLWEKey::decompress_repair(&corrupted).unwrap().b, key.b);
+        
+        // Intact sketches pass straight through
+        assert_eq!(RingLWEKey::decompress_repair(&compressed).unwrap().b, key.b);
+        
+        corrupted.anchor_coeffs[90] ^= 1;
+        assert!(matches!(
+            RingLWEKey::decompress_repair(&corrupted),
+            Err(ILCError::ReconstructionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_recovery_from_bit_errors() {
+        let (key, seed) = random_key();
+        let compressed = key.compress(seed);
+        assert_eq!(RingLWEKey::decompress_with_recovery(&compressed, 0).unwrap().b, key.b);
+        
+        let mut corrupted = compressed.clone();
+        corrupted.anchor_coeffs[40] ^= 1 << 4;
+        assert!(matches!(
+            RingLWEKey::decompress_with_recovery(&corrupted, 0),
+            Err(ILCError::ReconstructionFailed)
+        ));
+        assert_eq!(RingLWEKey::decompress_with_recovery(&corrupted, 1).unwrap().b, key.b);
+        
+        // A corrupted parity value only disturbs the odd coefficient
+        let mut corrupted = compressed.clone();
+        corrupted.parity[7] ^= 1 << 2;
+        assert_eq!(RingLWEKey::decompress_with_recovery(&corrupted, 1).unwrap().b, key.b);
+        
+        // A flip taking the parity to q or above
+        let mut edge = key.clone();
+        (edge.b.coeffs[14], edge.b.coeffs[15]) = (3328, 0);
+        let mut flipped = edge.compress(seed);
+        flipped.parity[7] ^= 1 << 2;
+        assert!(flipped.parity[7] as u32 >= Q);
+        assert_eq!(RingLWEKey::decompress_with_recovery(&flipped, 1).unwrap().b, edge.b);
+        
+        // An anchor flipped past q (the wire refuses it, but a sketch built
+        // in memory can hold it) is an error, not an overflow, and can be
+        // repaired
+        let mut high = key.clone();
+        (high.b.coeffs[0], high.b.coeffs[1]) = (1500, 1829);
+        let mut sketch = high.compress(seed);
+        sketch.anchor_coeffs[0] |= 1 << 11;
+        assert!(crate::to_bytes(&sketch).is_err());
+        assert!(matches!(RingLWEKey::decompress(&sketch), Err(ILCError::ChecksumMismatch)));
+        assert_eq!(RingLWEKey::decompress_with_recovery(&sketch, 1).unwrap().b, high.b);
+        let mut decimated = high.compress_with_decimation(seed, 4);
+        decimated.anchor_coeffs[0] |= 1 << 11;
+        assert!(RingLWEKey::decompress_decimated(&decimated).is_ok());
+        
+        // Two errors are past a budget of one
+        corrupted.anchor_coeffs[100] ^= 1;
+        assert!(matches!(
+            RingLWEKey::decompress_with_recovery(&corrupted, 1),
+            Err(ILCError::ReconstructionFailed)
+        ));
+    }
+
+    #[test]
+    fn test_difference_parity_roundtrip() {
+        let (key, seed) = random_key();
+        let compressed = key.compress_with_parity_mode(seed, ParityMode::Difference);
+        assert_eq!(compressed.parity_mode, ParityMode::Difference);
+        
+        let recovered = RingLWEKey::decompress(&compressed).unwrap();
+        assert_eq!(key.b.coeffs, recovered.b.coeffs);
+    }
+
+    #[test]
+    fn test_difference_parity_smaller_on_correlated_input() {
+        // Structured key: each odd coefficient is its even neighbour +/- 1
+        let seed = [9u8; 32];
+        let a = RingElement::from_seed(&seed, 0);
+        let mut b = RingElement::from_seed(&seed, 1);
+        for i in 0..N/2 {
+            let delta = if i % 2 == 0 { 1 } else { Q - 1 };
+            b.coeffs[2*i + 1] = ((b.coeffs[2*i] as u32 + delta) % Q) as u16;
+        }
+        let key = RingLWEKey { a, b };
+        
+        // Total centered magnitude as a proxy for entropy-coded size
+        let magnitude = |sketch: &CompressedPK| -> i32 {
+            sketch.parity.iter().map(|&p| centered_diff(p, 0).abs()).sum()
+        };
+        let sum = key.compress_with_parity_mode(seed, ParityMode::Sum);
+        let diff = key.compress_with_parity_mode(seed, ParityMode::Difference);
+        
+        assert_eq!(magnitude(&diff), (N/2) as i32);
+        assert!(magnitude(&diff) * 10 < magnitude(&sum));
+        assert_eq!(RingLWEKey::decompress(&diff).unwrap().b, key.b);
+    }
+
+    #[test]
+    fn test_anchors_only_roundtrip() {
+        let (key, seed) = random_key();
+        let compressed = key.compress_anchors_only(seed);
+        assert!(compressed.parity.is_empty());
+        
+        let decoded = crate::from_bytes(&crate::to_bytes(&compressed).unwrap()).unwrap();
+        assert_eq!(RingLWEKey::decompress(&decoded).unwrap().b, key.b);
+        
+        let mut streamed = Vec::new();
+        decompress_streaming(&decoded, |_, c| streamed.push(c)).unwrap();
+        assert_eq!(streamed, key.b.to_vec());
+    }
 
-impl AlgebraicShield for RingLWEKey {
-    /// Compress the public key using algebraic sketching
-    fn compress(&self, seed: [u8; 32]) -> CompressedPK {
-        // Extract anchor coefficients (even indices)
-        let anchor_coeffs: Vec<u16> = self.b.coeffs
-            .iter()
-            .enumerate()
-            .filter(|(i, _)| i % 2 == 0)
-            .map(|(_, &c)| c)
+    #[test]
+    fn test_missing_parity_rejected() {
+        let (key, seed) = random_key();
+        
+        // Parity dropped from a sketch that declares it
+        let mut truncated = key.compress(seed);
+        truncated.parity.clear();
+        assert!(matches!(RingLWEKey::decompress(&truncated), Err(ILCError::InvalidInput)));
+        
+        // Half the anchors of a parity-free sketch
+        let mut truncated = key.compress_anchors_only(seed);
+        truncated.anchor_coeffs.truncate(N/2);
+        assert!(matches!(RingLWEKey::decompress(&truncated), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_pattern_roundtrip() {
+        let (key, seed) = random_key();
+        
+        // Irregular runs: anchors at multiples of 3 plus a few extras
+        let mut keep = [false; N];
+        for (i, k) in keep.iter_mut().enumerate() {
+            *k = i % 3 == 0 || i % 7 == 5;
+        }
+        let sketch = key.compress_with_pattern(seed, &keep).unwrap();
+        assert_eq!(sketch.anchor_coeffs.len(), keep.iter().filter(|&&k| k).count());
+        assert_eq!(sketch.anchor_coeffs.len() + sketch.parity.len(), N);
+        
+        let recovered = RingLWEKey::decompress_patterned(&sketch).unwrap();
+        assert_eq!(key.b, recovered.b);
+        
+        // One anchor for the whole polynomial still chains through
+        let mut keep = [false; N];
+        keep[0] = true;
+        let sketch = key.compress_with_pattern(seed, &keep).unwrap();
+        assert_eq!(RingLWEKey::decompress_patterned(&sketch).unwrap().b, key.b);
+    }
+
+    #[test]
+    fn test_pattern_without_leading_anchor_rejected() {
+        let (key, seed) = random_key();
+        let mut keep = [true; N];
+        keep[0] = false;
+        assert!(matches!(key.compress_with_pattern(seed, &keep), Err(ILCError::InvalidInput)));
+        
+        // A sketch whose mask doesn't match its vectors is rejected too
+        let mut sketch = key.compress_with_pattern(seed, &[true; N]).unwrap();
+        sketch.keep_mask[3] = 0;
+        assert!(matches!(RingLWEKey::decompress_patterned(&sketch), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_delta_between_key_versions() {
+        let (old, seed) = random_key();
+        let mut new = old.clone();
+        for i in [3, 100, 255] {
+            new.b.coeffs[i] = (new.b.coeffs[i] + 1) % Q as u16;
+        }
+        
+        let delta = new.compress_delta_from(&old, seed);
+        assert_eq!(delta.changes.iter().map(|&(i, _)| i).collect::<Vec<_>>(), vec![3, 100, 255]);
+        assert!(delta.size_bytes() * 5 < new.compress(seed).size_bytes());
+        
+        let recovered = RingLWEKey::decompress_delta(&delta, &old).unwrap();
+        assert_eq!(recovered.b, new.b);
+        assert_eq!(recovered.a, new.a);
+        
+        // Applied to the wrong base key
+        assert!(matches!(
+            RingLWEKey::decompress_delta(&delta, &new),
+            Err(ILCError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_module_lwe_roundtrip_rank_3() {
+        let seed = rand::thread_rng().gen::<[u8; 32]>();
+        let a = ModuleLWEKey::expand_a(&seed, 3);
+        // Secret and error vectors from domains clear of the `A` entries
+        let s: Vec<_> = (0..3).map(|j| RingElement::from_seed(&seed, 0xf0 + j)).collect();
+        let e: Vec<_> = (0..3).map(|i| RingElement::from_seed(&seed, 0xf8 + i)).collect();
+        let t: Vec<_> = (0..3)
+            .map(|i| (0..3).fold(e[i].clone(), |acc, j| a[i][j].mul_add(&s[j], &acc)))
             .collect();
+        let key = ModuleLWEKey { a_seed: seed, t };
         
-        // Compute parity: sum of adjacent pairs mod q
-        // This allows reconstruction: if we know anchor[i] and parity[i],
-        // we can recover odd[i] = parity[i] - anchor[i] mod q
-        let parity: Vec<u16> = (0..N/2)
-            .map(|i| {
-                let even = self.b.coeffs[2*i] as u32;
-                let odd = self.b.coeffs[2*i + 1] as u32;
-                ((even + odd) % Q) as u16
+        let compressed = key.compress(seed);
+        assert_eq!(compressed.rank, 3);
+        
+        let recovered = ModuleLWEKey::decompress(&compressed).unwrap();
+        assert_eq!(recovered.t, key.t);
+        assert_eq!(recovered.matrix_a(), a);
+        assert_ne!(a[0][1], a[1][0]);
+        
+        // A module sketch is not a single-polynomial one, and vice versa
+        assert!(matches!(RingLWEKey::decompress(&compressed), Err(ILCError::InvalidInput)));
+        let (single, seed) = random_key();
+        assert_eq!(ModuleLWEKey::decompress(&single.compress(seed)).unwrap().t, vec![single.b]);
+        
+        let mut tampered = compressed;
+        tampered.anchor_coeffs[200] = (tampered.anchor_coeffs[200] + 1) % Q as u16;
+        assert!(matches!(ModuleLWEKey::decompress(&tampered), Err(ILCError::ChecksumMismatch)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_module_lwe_rank_too_large() {
+        let seed = [1u8; 32];
+        let t = vec![RingElement::from_seed(&seed, 0); ModuleLWEKey::MAX_RANK + 1];
+        ModuleLWEKey { a_seed: seed, t }.compress(seed);
+    }
+
+    #[test]
+    fn test_balanced_parity_roundtrip() {
+        let (key, seed) = random_key();
+        for mode in [ParityMode::Sum, ParityMode::Difference] {
+            let standard = key.compress_with_parity_mode(seed, mode);
+            let balanced = standard.to_balanced().unwrap();
+            assert!(balanced.parity.iter().all(|&p| (-(Q as i16) / 2..=Q as i16 / 2).contains(&p)));
+            assert_eq!(balanced.to_standard(), standard);
+            assert_eq!(RingLWEKey::decompress_balanced(&balanced).unwrap().b, key.b);
+        }
+        
+        // Sum parity of (0, x) is x: hit the centering boundaries
+        let mut b = RingElement::default();
+        for (i, x) in [0u16, 1, Q as u16 / 2, Q as u16 / 2 + 1, Q as u16 - 1].into_iter().enumerate() {
+            b.coeffs[2*i + 1] = x;
+        }
+        let edge = RingLWEKey { a: key.a.clone(), b };
+        let balanced = edge.compress_balanced(seed);
+        assert_eq!(balanced.parity[..5], [0, 1, 1664, -1664, -1]);
+        assert_eq!(RingLWEKey::decompress_balanced(&balanced).unwrap().b, edge.b);
+        
+        assert!(key.compress_anchors_only(seed).to_balanced().is_err());
+    }
+
+    #[test]
+    fn test_parity_opt() {
+        // Uniform parity needs the full width: the fallback matches the
+        // 12-bit packing exactly
+        let (key, seed) = random_key();
+        let narrow = key.compress_parity_opt(seed);
+        assert_eq!(narrow.parity_bits, 12);
+        assert_eq!(narrow.coeff_bytes(), key.compress(seed).coeff_bytes());
+        assert_eq!(RingLWEKey::decompress_parity_opt(&narrow).unwrap().b, key.b);
+        
+        // Neighbouring coefficients within +-20 of each other: difference
+        // parity fits 6 bits
+        let mut smooth = RingElement::default();
+        for (i, c) in smooth.coeffs.iter_mut().enumerate() {
+            *c = ((i / 2 * 997) as u32 % Q) as u16;
+            if i % 2 == 1 {
+                *c = ((*c as u32 + Q - 20 + (i as u32 % 41)) % Q) as u16;
+            }
+        }
+        let smooth = RingLWEKey { a: key.a.clone(), b: smooth };
+        let narrow = smooth.compress_parity_opt(seed);
+        assert_eq!(narrow.parity_mode, ParityMode::Difference);
+        assert_eq!(narrow.parity_bits, 6);
+        assert_eq!(narrow.parity.len(), N / 2 * 6 / 8);
+        assert!(narrow.coeff_bytes() < smooth.compress(seed).coeff_bytes());
+        assert_eq!(RingLWEKey::decompress_parity_opt(&narrow).unwrap().b, smooth.b);
+        
+        let mut truncated = narrow.clone();
+        truncated.parity.pop();
+        assert!(matches!(RingLWEKey::decompress_parity_opt(&truncated), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_dyn_strategies() {
+        let (key, seed) = random_key();
+        let strategies: Vec<Box<dyn DynStrategy>> = vec![
+            Box::new(ParityMode::Sum),
+            Box::new(ParityMode::Difference),
+            Box::new(|k: &RingLWEKey, s| Ok(k.compress_anchors_only(s))),
+            Box::new(|k: &RingLWEKey, s| Ok(k.compress_with_mac(s, b"plugin"))),
+        ];
+        
+        let sketches: Vec<_> = strategies.iter().map(|s| s.compress_dyn(&key, seed).unwrap()).collect();
+        assert_eq!(sketches[1].parity_mode, ParityMode::Difference);
+        assert!(!sketches[2].has_parity);
+        for sketch in &sketches[..3] {
+            assert_eq!(RingLWEKey::decompress(sketch).unwrap().b, key.b);
+        }
+        assert_eq!(RingLWEKey::decompress_verify_mac(&sketches[3], b"plugin").unwrap().b, key.b);
+    }
+
+    #[test]
+    fn test_decompress_with_fallback() {
+        let (key, seed) = random_key();
+        
+        // Sender used difference parity but labelled the sketch as sum
+        let mut mislabelled = key.compress_with_parity_mode(seed, ParityMode::Difference);
+        mislabelled.parity_mode = ParityMode::Sum;
+        assert!(matches!(RingLWEKey::decompress(&mislabelled), Err(ILCError::ChecksumMismatch)));
+        
+        let chain = [StrategyId::Standard, StrategyId::DifferenceParity];
+        assert_eq!(RingLWEKey::decompress_with_fallback(&mislabelled, &chain).unwrap().b, key.b);
+        
+        // Flipped anchor bit: only repair gets there
+        let mut flipped = key.compress(seed);
+        flipped.anchor_coeffs[10] ^= 1 << 3;
+        assert!(matches!(
+            RingLWEKey::decompress_with_fallback(&flipped, &chain),
+            Err(ILCError::ChecksumMismatch)
+        ));
+        let chain = [StrategyId::Standard, StrategyId::Repair];
+        assert_eq!(RingLWEKey::decompress_with_fallback(&flipped, &chain).unwrap().b, key.b);
+        
+        assert!(matches!(RingLWEKey::decompress_with_fallback(&flipped, &[]), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_compress_checked_rejects_foreign_a() {
+        let (key, seed) = random_key();
+        assert_eq!(key.compress_checked(seed).unwrap(), key.compress(seed));
+        
+        let foreign = RingLWEKey { a: RingElement::from_seed(&[0xAA; 32], 0), b: key.b.clone() };
+        assert!(matches!(foreign.compress_checked(seed), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_decimation() {
+        let (key, seed) = random_key();
+        let sketch = key.compress_with_decimation(seed, 2);
+        assert_eq!(sketch, key.compress(seed));
+        assert_eq!(RingLWEKey::decompress_decimated(&sketch).unwrap().b, key.b);
+        
+        // A smooth b (a slow ramp) survives k = 4 within a small tolerance
+        let mut smooth = RingElement::default();
+        for (i, c) in smooth.coeffs.iter_mut().enumerate() {
+            *c = (i as u16 * 5 + (i as u16 % 3)) % Q as u16;
+        }
+        let key = RingLWEKey { a: key.a.clone(), b: smooth };
+        let sketch = key.compress_with_decimation(seed, 4);
+        assert_eq!(sketch.anchor_coeffs.len(), N / 4);
+        assert!(sketch.size_bytes() < key.compress(seed).size_bytes() / 2);
+        assert!(matches!(RingLWEKey::decompress(&sketch), Err(ILCError::InvalidInput)));
+        
+        let recovered = RingLWEKey::decompress_decimated(&sketch).unwrap();
+        let diff = recovered.b.sub(&key.b);
+        // Interior gaps are off by the ramp's jitter; only the wrap-around
+        // gap towards -b[0] is far from the ramp
+        assert!(diff.coeffs[..N - 4].iter().all(|&d| RingElement::new([d; N]).norm_inf() <= 3));
+    }
+
+    #[cfg(feature = "float")]
+    #[test]
+    fn test_autotune() {
+        let a = RingElement::from_seed(&[3u8; 32], 0);
+        // Jittered ramps through zero at N/2, so the wrap-around to -b[0]
+        // continues the ramp too
+        let samples: Vec<RingLWEKey> = (0..4i32)
+            .map(|k| {
+                let mut b = RingElement::default();
+                for (i, c) in b.coeffs.iter_mut().enumerate() {
+                    let i = i as i32;
+                    *c = ((k + 2) * (i - N as i32 / 2) + i % 3).rem_euclid(Q as i32) as u16;
+                }
+                RingLWEKey { a: a.clone(), b }
             })
             .collect();
+
+        let loose = autotune(&samples, 0.5, 4);
+        let tight = autotune(&samples, 0.15, 4);
+        assert_eq!(loose, CompressionLevel::Lossless);
+        assert!(tight > loose);
+
+        // Every sample decodes within the bound at the chosen level
+        for key in &samples {
+            let sketch = key.compress_with_decimation([0; 32], tight.decimation());
+            let recovered = RingLWEKey::decompress_decimated(&sketch).unwrap();
+            assert!(recovered.b.sub(&key.b).norm_inf() <= 4);
+        }
+
+        // The chosen level meets the target on the sample
+        let ratio = |level: CompressionLevel| {
+            let wire: usize = samples
+                .iter()
+                .map(|key| crate::to_bytes_unchecked(&key.compress_with_decimation([0; 32], level.decimation())).len())
+                .sum();
+            wire as f64 / samples.iter().map(RingLWEKey::size_bytes).sum::<usize>() as f64
+        };
+        assert!(ratio(tight) <= 0.15);
+
+        // Unreachable target: the most aggressive level within the bound
+        assert_eq!(autotune(&samples, 0.0, 4), CompressionLevel::Decimate16);
+        assert_eq!(autotune(&samples, 0.0, 0), CompressionLevel::Lossless);
+        assert_eq!(autotune(&[], 0.1, 4), CompressionLevel::Lossless);
+
+        // Random keys are far from smooth: nothing lossy qualifies
+        let random: Vec<RingLWEKey> = (0..4).map(|_| random_key().0).collect();
+        assert_eq!(autotune(&random, 0.0, 8), CompressionLevel::Lossless);
+    }
+
+    #[test]
+    fn test_size_class() {
+        let (key, seed) = random_key();
+        let mut sketch = key.compress(seed);
+        assert_eq!(sketch.size_class().unwrap(), SizeClass::Medium);
+        assert_eq!(key.compress_anchors_only(seed).size_class().unwrap(), SizeClass::Medium);
         
-        let checksum = compute_checksum(&self.b);
+        // Buckets follow the encoding, not size_bytes: a signature that
+        // size_bytes puts just under 768 lands the wire form over it
+        let wire = crate::to_bytes(&sketch).unwrap().len();
+        sketch.attach_signature(vec![0u8; 767 - wire]);
+        assert!(sketch.size_bytes() < 768);
+        assert_eq!(sketch.size_class().unwrap(), SizeClass::Large);
         
-        CompressedPK {
-            seed,
-            anchor_coeffs,
-            checksum,
-            parity,
+        sketch.parity[0] = 1 << 12;
+        assert!(matches!(sketch.size_class(), Err(ILCError::Serialization(_))));
+    }
+
+    #[test]
+    fn test_sketch_patch_roundtrip() {
+        let (old, seed) = random_key();
+        let mut new = old.clone();
+        for i in [3, 100, 255] {
+            new.b.coeffs[i] = (new.b.coeffs[i] + 1) % Q as u16;
         }
+        let base = old.compress(seed);
+        let target = new.compress(seed);
+        
+        let patch = base.diff(&target);
+        assert_eq!(base.apply_patch(&patch).unwrap(), target);
+        assert!(patch.size_bytes() * 5 < target.size_bytes());
+        assert_eq!(RingLWEKey::decompress(&base.apply_patch(&patch).unwrap()).unwrap().b, new.b);
+        
+        // Changing layout and header fields
+        let target = new.compress_anchors_only(seed).with_seed_id(9);
+        assert_eq!(base.apply_patch(&base.diff(&target)).unwrap(), target);
+        
+        let mut bad = base.diff(&target);
+        bad.anchor_changes.push((N as u16, 0));
+        assert!(matches!(base.apply_patch(&bad), Err(ILCError::InvalidInput)));
     }
-    
-    /// Decompress and reconstruct the public key
-    fn decompress(sketch: &CompressedPK) -> Result<Self, ILCError> {
-        // Regenerate 'a' from seed
-        let a = RingElement::from_seed(&sketch.seed, 0);
+
+    #[test]
+    fn test_shuffled_roundtrip() {
+        let (key, seed) = random_key();
+        let shuffle_key = [0x5Au8; 32];
+        let plain = key.compress(seed);
+        let shuffled = key.compress_shuffled(seed, &shuffle_key);
+        assert_ne!(shuffled.anchor_coeffs, plain.anchor_coeffs);
         
-        // Reconstruct 'b' from anchors and parity
-        let mut b = RingElement::default();
+        let recovered = RingLWEKey::decompress_shuffled(&shuffled, &shuffle_key).unwrap();
+        assert_eq!(key.b, recovered.b);
         
-        if sketch.anchor_coeffs.len() != N/2 || sketch.parity.len() != N/2 {
-            return Err(ILCError::InvalidInput);
+        assert!(matches!(
+            RingLWEKey::decompress_shuffled(&shuffled, &[0x5Bu8; 32]),
+            Err(ILCError::ChecksumMismatch)
+        ));
+        assert!(matches!(RingLWEKey::decompress(&shuffled), Err(ILCError::ChecksumMismatch)));
+    }
+
+    #[test]
+    fn test_interleaved_roundtrip() {
+        let (key, seed) = random_key();
+        let compressed = key.compress_with_parity_mode(seed, ParityMode::Difference);
+        let interleaved = compressed.to_interleaved().unwrap();
+        assert_eq!(interleaved.pairs[5], (compressed.anchor_coeffs[5], compressed.parity[5]));
+        
+        let recovered = RingLWEKey::decompress_interleaved(&interleaved).unwrap();
+        assert_eq!(key.b, recovered.b);
+        
+        let back = interleaved.to_separate();
+        assert_eq!(back.anchor_coeffs, compressed.anchor_coeffs);
+        assert_eq!(back.parity, compressed.parity);
+        assert_eq!(RingLWEKey::decompress(&back).unwrap().b, key.b);
+        
+        assert!(key.compress_anchors_only(seed).to_interleaved().is_err());
+    }
+
+    #[test]
+    fn test_flipped_checksum_byte_rejected() {
+        let (key, seed) = random_key();
+        let compressed = key.compress(seed);
+        // First and last bytes alike: no early exit to tell them apart
+        for i in 0..8 {
+            let mut tampered = compressed.clone();
+            tampered.checksum[i] ^= 0x01;
+            assert!(matches!(RingLWEKey::decompress(&tampered), Err(ILCError::ChecksumMismatch)));
         }
+        assert!(ct_eq(&compressed.checksum, &compressed.checksum));
+    }
+
+    #[test]
+    fn test_checksum_kinds() {
+        // The standard check value for CRC-32/IEEE
+        assert_eq!(!crc32_update(!0, b"123456789"), 0xCBF4_3926);
         
-        for i in 0..N/2 {
-            let anchor = sketch.anchor_coeffs[i] as u32;
-            let parity = sketch.parity[i] as u32;
-            
-            // Even coefficient is the anchor
-            b.coeffs[2*i] = anchor as u16;
+        let (key, seed) = random_key();
+        assert_eq!(key.compress_with_checksum(seed, ChecksumKind::Sha3).unwrap(), key.compress(seed));
+        for kind in [ChecksumKind::Sha3, ChecksumKind::Crc32, ChecksumKind::None] {
+            let compressed = key.compress_with_checksum(seed, kind).unwrap();
+            assert_eq!(RingLWEKey::decompress(&compressed).unwrap().b, key.b);
+            assert_eq!(decompress_centered(&compressed).unwrap(), key.b.to_centered());
             
-            // Odd coefficient: parity - anchor mod q
-            b.coeffs[2*i + 1] = ((parity + Q - anchor) % Q) as u16;
+            let mut corrupted = compressed.clone();
+            corrupted.anchor_coeffs[9] ^= 1 << 2;
+            let result = RingLWEKey::decompress(&corrupted);
+            if kind == ChecksumKind::None {
+                assert_ne!(result.unwrap().b, key.b);
+            } else {
+                assert!(matches!(result, Err(ILCError::ChecksumMismatch)));
+                assert_eq!(RingLWEKey::decompress_repair(&corrupted).unwrap().b, key.b);
+            }
         }
         
-        // Verify checksum
-        let computed_checksum = compute_checksum(&b);
-        if computed_checksum != sketch.checksum {
-            return Err(ILCError::ChecksumMismatch);
+        // CRC-32 leaves the upper half of the checksum unused
+        assert_eq!(key.compress_with_checksum(seed, ChecksumKind::Crc32).unwrap().checksum[4..], [0; 4]);
+        assert!(key.compress_with_checksum(seed, ChecksumKind::Crc32).unwrap().to_balanced().is_err());
+        
+        // MAC and chained tags need their own constructors
+        for kind in [ChecksumKind::Mac, ChecksumKind::Chained] {
+            assert!(matches!(key.compress_with_checksum(seed, kind), Err(ILCError::InvalidInput)));
         }
         
-        Ok(RingLWEKey { a, b })
+        // MAC and chained tags are labelled as such and only verify through
+        // their own decoders
+        let mac = key.compress_with_mac(seed, b"k");
+        let chained = key.compress_chained(seed, [0; 8]);
+        assert_eq!((mac.checksum_kind, chained.checksum_kind), (ChecksumKind::Mac, ChecksumKind::Chained));
+        for sketch in [&mac, &chained] {
+            assert!(matches!(RingLWEKey::decompress(sketch), Err(ILCError::InvalidInput)));
+            assert!(matches!(decompress_centered(sketch), Err(ILCError::InvalidInput)));
+        }
+        assert!(matches!(RingLWEKey::decompress_verify_mac(&chained, b"k"), Err(ILCError::InvalidInput)));
+        assert!(matches!(RingLWEKey::decompress_chained(&mac, [0; 8]), Err(ILCError::InvalidInput)));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use rand::Rng;
+    #[test]
+    fn test_mac_verification() {
+        let (key, seed) = random_key();
+        let compressed = key.compress_with_mac(seed, b"shared secret");
+        
+        let recovered = RingLWEKey::decompress_verify_mac(&compressed, b"shared secret").unwrap();
+        assert_eq!(key.b.coeffs, recovered.b.coeffs);
+        
+        assert!(matches!(
+            RingLWEKey::decompress_verify_mac(&compressed, b"wrong secret"),
+            Err(ILCError::ChecksumMismatch)
+        ));
+        
+        let mut tampered = compressed.clone();
+        tampered.anchor_coeffs[0] = (tampered.anchor_coeffs[0] + 1) % Q as u16;
+        assert!(RingLWEKey::decompress_verify_mac(&tampered, b"shared secret").is_err());
+    }
 
-    fn random_key() -> (RingLWEKey, [u8; 32]) {
-        let seed = rand::thread_rng().gen::<[u8; 32]>();
-        let a = RingElement::from_seed(&seed, 0);
-        let s = RingElement::from_seed(&seed, 1); // secret
-        let e = RingElement::from_seed(&seed, 2); // error (small in practice)
-        let b = a.mul(&s).add(&e);
+    #[test]
+    fn test_chained_log() {
+        let genesis = [0u8; 8];
+        let mut log: Vec<CompressedPK> = Vec::new();
+        for i in 0..4u8 {
+            let seed = [i; 32];
+            let key = RingLWEKey { a: RingElement::from_seed(&seed, 0), b: RingElement::from_seed(&seed, 1) };
+            let prev = log.last().map_or(genesis, |s| s.checksum);
+            log.push(key.compress_chained(seed, prev));
+        }
+        verify_chain(&log, genesis).unwrap();
+        let recovered = RingLWEKey::decompress_chained(&log[2], log[1].checksum).unwrap();
+        assert_eq!(recovered.b, RingElement::from_seed(&[2u8; 32], 1));
+        assert!(RingLWEKey::decompress(&log[2]).is_err());
         
-        (RingLWEKey { a, b }, seed)
+        // Rewriting an earlier entry with a self-consistent checksum still
+        // breaks the link from the entry after it
+        let mut tampered = log.clone();
+        tampered[1].anchor_coeffs[0] = (tampered[1].anchor_coeffs[0] + 1) % Q as u16;
+        let b = reconstruct_b(&tampered[1]).unwrap();
+        tampered[1].checksum = compute_chained_checksum(&b, &tampered[0].checksum);
+        RingLWEKey::decompress_chained(&tampered[1], tampered[0].checksum).unwrap();
+        assert!(matches!(verify_chain(&tampered, genesis), Err(ILCError::ChecksumMismatch)));
+        
+        // Dropping an entry is caught the same way
+        let truncated = [log[0].clone(), log[2].clone()];
+        assert!(verify_chain(&truncated, genesis).is_err());
     }
 
     #[test]
-    fn test_compress_decompress_roundtrip() {
+    fn test_streaming_matches_decompress() {
         let (key, seed) = random_key();
         let compressed = key.compress(seed);
+        
+        let mut streamed = Vec::new();
+        decompress_streaming(&compressed, |i, c| streamed.push((i, c))).unwrap();
+        
         let recovered = RingLWEKey::decompress(&compressed).unwrap();
+        let expected: Vec<(usize, u16)> = recovered.b.coeffs.iter().copied().enumerate().collect();
+        assert_eq!(streamed, expected);
         
-        assert_eq!(key.b.coeffs, recovered.b.coeffs);
+        let mut corrupted = compressed.clone();
+        corrupted.parity[3] = (corrupted.parity[3] + 1) % Q as u16;
+        assert!(matches!(
+            decompress_streaming(&corrupted, |_, _| {}),
+            Err(ILCError::ChecksumMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_decompress_centered_matches_to_centered() {
+        let (key, seed) = random_key();
+        let compressed = key.compress(seed);
+        
+        let centered = decompress_centered(&compressed).unwrap();
+        assert_eq!(centered, RingLWEKey::decompress(&compressed).unwrap().b.to_centered());
+        assert!(centered.iter().all(|c| (-(Q as i16) / 2..=Q as i16 / 2).contains(c)));
+    }
+
+    #[test]
+    fn test_correlated_roundtrip_and_size() {
+        // Three keys for the same 'a' whose b differ by small noise
+        let (key, seed) = random_key();
+        let mut rng = rand::thread_rng();
+        let bs: Vec<RingElement> = (0..3)
+            .map(|_| {
+                let mut b = key.b.clone();
+                for c in &mut b.coeffs {
+                    *c = ((*c as u32 + rng.gen_range(0..5u32) + Q - 2) % Q) as u16;
+                }
+                b
+            })
+            .collect();
+        
+        let sketch = compress_correlated(&bs, seed);
+        let keys = decompress_correlated(&sketch).unwrap();
+        assert_eq!(keys.len(), 3);
+        for (k, b) in keys.iter().zip(&bs) {
+            assert_eq!(k.a, key.a);
+            assert_eq!(&k.b, b);
+        }
+        
+        // Deltas of noise in [-4, 4] zigzag below 2^4
+        assert_eq!(sketch.entries[0].bits, 12);
+        assert!(sketch.entries[1..].iter().all(|e| e.bits <= 4));
+        
+        // The saving is in the deltas, not just the shared seed: the same
+        // number of unrelated keys under one seed packs every entry whole
+        let unrelated: Vec<RingElement> = (0..3u8).map(|d| RingElement::from_seed(&seed, 0x80 + d)).collect();
+        let baseline = compress_correlated(&unrelated, seed);
+        assert!(baseline.entries.iter().all(|e| e.bits == 12));
+        assert!(sketch.size_bytes() * 3 < baseline.size_bytes() * 2);
+        assert_eq!(decompress_correlated(&baseline).unwrap()[2].b, unrelated[2]);
+        
+        let mut truncated = sketch.clone();
+        truncated.entries[1].coeffs.pop();
+        assert!(matches!(decompress_correlated(&truncated), Err(ILCError::InvalidInput)));
+    }
+
+    #[test]
+    fn test_combined_checksum_is_order_sensitive() {
+        let parts: Vec<RingElement> = (0..3u8).map(|d| RingElement::from_seed(&[5u8; 32], d)).collect();
+        let checksums: Vec<[u8; 8]> = parts.iter().map(compute_checksum).collect();
+        let combined = combine_checksums(&checksums);
+        
+        let swapped = [checksums[1], checksums[0], checksums[2]];
+        assert_ne!(combine_checksums(&swapped), combined);
+        
+        verify_combined(&parts, &combined).unwrap();
+        let reordered = [parts[1].clone(), parts[0].clone(), parts[2].clone()];
+        assert!(matches!(verify_combined(&reordered, &combined), Err(ILCError::ChecksumMismatch)));
+        assert!(verify_combined(&parts[..2], &combined).is_err());
+    }
+
+    #[test]
+    fn test_malformed_sketch_skips_a_generation() {
+        use crate::ring::FROM_SEED_CALLS;
+        
+        let (key, seed) = random_key();
+        let mut malformed = key.compress(seed);
+        malformed.parity.pop();
+        
+        let before = FROM_SEED_CALLS.with(|c| c.get());
+        assert!(matches!(RingLWEKey::decompress(&malformed), Err(ILCError::InvalidInput)));
+        assert_eq!(FROM_SEED_CALLS.with(|c| c.get()), before);
+        
+        // A well-formed sketch does regenerate 'a'
+        RingLWEKey::decompress(&key.compress(seed)).unwrap();
+        assert_eq!(FROM_SEED_CALLS.with(|c| c.get()), before + 1);
+    }
+
+    #[test]
+    fn test_seed_id_resolved_via_registry() {
+        let (key, seed) = random_key();
+        let inline = key.compress(seed);
+        let by_id = inline.clone().with_seed_id(7);
+        assert_eq!(inline.size_bytes() - by_id.size_bytes(), 28);
+        
+        let registry = |id: u32| (id == 7).then_some(seed);
+        let recovered = RingLWEKey::decompress_with_registry(&by_id, registry).unwrap();
+        assert_eq!(key.a, recovered.a);
+        assert_eq!(key.b, recovered.b);
+        
+        assert!(matches!(RingLWEKey::decompress(&by_id), Err(ILCError::UnknownSeedId(7))));
+    }
+
+    #[test]
+    fn test_nonzero_a_domain_roundtrip() {
+        let seed = rand::thread_rng().gen::<[u8; 32]>();
+        let a = RingElement::from_seed(&seed, 5);
+        let b = a.mul(&RingElement::from_seed(&seed, 1)).add(&RingElement::from_seed(&seed, 2));
+        let key = RingLWEKey { a, b };
+        
+        let compressed = key.compress(seed).with_a_domain(5);
+        let decoded = crate::from_bytes(&crate::to_bytes(&compressed).unwrap()).unwrap();
+        assert_eq!(decoded.a_domain, 5);
+        
+        let recovered = RingLWEKey::decompress(&decoded).unwrap();
+        assert_eq!(key.a, recovered.a);
+        assert_eq!(key.b, recovered.b);
+        
+        // The default domain regenerates a different 'a'
+        let recovered = RingLWEKey::decompress(&key.compress(seed)).unwrap();
+        assert_ne!(key.a, recovered.a);
+    }
+
+    #[test]
+    fn test_metrics_callback() {
+        let (key, seed) = random_key();
+        let mut reported = Vec::new();
+        let compressed = key.compress_with_metrics(seed, &mut |m| reported.push(m));
+        
+        assert_eq!(reported.len(), 1);
+        let wire_bytes = crate::to_bytes(&compressed).unwrap().len();
+        assert_eq!(reported[0].wire_bytes, wire_bytes);
+        assert_eq!(reported[0].key_bytes, key.size_bytes());
+        #[cfg(feature = "float")]
+        assert_eq!(reported[0].ratio, wire_bytes as f64 / key.size_bytes() as f64);
+        assert_eq!(RingLWEKey::decompress(&compressed).unwrap().b, key.b);
+    }
+
+    #[test]
+    fn test_ratio_permille_matches_float() {
+        let (key, seed) = random_key();
+        let mut reported = None;
+        key.compress_with_metrics(seed, &mut |m| reported = Some(m));
+        let m = reported.unwrap();
+        
+        let float = m.wire_bytes as f64 / m.key_bytes as f64 * 1000.0;
+        assert!((m.ratio_permille() as f64 - float).abs() <= 0.5);
+        
+        let exact = CompressionMetrics { wire_bytes: 500, key_bytes: 1000, ..m };
+        assert_eq!(exact.ratio_permille(), 500);
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        let (key, seed) = random_key();
+        let mut signed = key.compress(seed);
+        let unsigned = signed.canonical_bytes();
+        assert_eq!(key.compress(seed).canonical_bytes(), unsigned);
+        
+        signed.attach_signature(vec![0xCD; 64]);
+        assert_eq!(signed.canonical_bytes(), unsigned);
+        #[cfg(feature = "seq")]
+        assert_eq!(signed.clone().with_seq(9).canonical_bytes(), unsigned);
+        
+        // Anything that changes what decodes is covered
+        let mut changed = signed.clone();
+        changed.parity_mode = ParityMode::Difference;
+        assert_ne!(changed.canonical_bytes(), unsigned);
+        assert_ne!(signed.clone().with_seed_id(1).canonical_bytes(), unsigned);
+        changed = signed.clone();
+        changed.anchor_coeffs[0] ^= 1;
+        assert_ne!(changed.canonical_bytes(), unsigned);
+    }
+
+    #[test]
+    fn test_signature_does_not_affect_b() {
+        let (key, seed) = random_key();
+        let mut compressed = key.compress(seed);
+        let checksum = compressed.checksum;
+        
+        compressed.attach_signature(vec![0xAB; 64]);
+        assert_eq!(compressed.checksum, checksum);
+        let decoded = crate::from_bytes(&crate::to_bytes(&compressed).unwrap()).unwrap();
+        assert_eq!(decoded.signature.as_deref(), Some(&[0xAB; 64][..]));
+        assert_eq!(RingLWEKey::decompress(&decoded).unwrap().b, key.b);
+        
+        assert_eq!(compressed.take_signature(), Some(vec![0xAB; 64]));
+        assert_eq!(compressed.take_signature(), None);
+        assert_eq!(RingLWEKey::decompress(&compressed).unwrap().b, key.b);
+    }
+
+    #[test]
+    fn test_heap_bytes() {
+        let (key, seed) = random_key();
+        let compressed = key.compress(seed);
+        assert_eq!(compressed.anchor_coeffs.capacity(), N/2);
+        assert_eq!(compressed.parity.capacity(), N/2);
+        assert_eq!(compressed.heap_bytes(), N * 2);
+        
+        let mut grown = compressed.clone();
+        grown.parity.reserve_exact(N/2);
+        assert_eq!(grown.heap_bytes(), compressed.heap_bytes() + (grown.parity.capacity() - N/2) * 2);
     }
 
     #[test]
@@ -126,4 +2229,31 @@ mod tests {
         // Should achieve ~50% compression with this MVP approach
         assert!(ratio < 0.75, "Compression ratio should be under 75%");
     }
+
+    #[test]
+    fn test_recovered_vs_transmitted() {
+        let (key, seed) = random_key();
+
+        // 'a' comes entirely from the seed; 'b' still costs N coefficients,
+        // half as anchors and half as parity, so nothing of 'b' is saved
+        assert_eq!(key.compress(seed).recovered_vs_transmitted(), (N, N));
+        assert_eq!(key.compress_anchors_only(seed).recovered_vs_transmitted(), (N, N));
+
+        let module = ModuleLWEKey { a_seed: seed, t: vec![key.b.clone(); 2] };
+        assert_eq!(module.compress(seed).recovered_vs_transmitted(), (2 * N, 2 * N));
+    }
+
+    #[cfg(feature = "seq")]
+    #[test]
+    fn test_seq_roundtrip() {
+        let (key, seed) = random_key();
+        let compressed = key.compress(seed).with_seq(42);
+
+        let bytes = crate::to_bytes(&compressed).unwrap();
+        let decoded = crate::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.seq, 42);
+
+        let recovered = RingLWEKey::decompress(&decoded).unwrap();
+        assert_eq!(key.b.coeffs, recovered.b.coeffs);
+    }
 }
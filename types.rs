@@ -1,10 +1,12 @@
 //! Core data structures for ILC
 
 use serde::{Serialize, Deserialize};
-use crate::ring::{RingElement, N, Q};
+use crate::ring::{barrett_reduce, RingElement, N, Q};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Standard RLWE public key: pk = (a, b) where b = a*s + e
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RingLWEKey {
     pub a: RingElement,
     pub b: RingElement,
@@ -18,39 +20,958 @@ impl RingLWEKey {
     }
 }
 
+/// Module-LWE public key (Kyber-768/1024 style): `t = A * s + e` with
+/// `t` in R_q^k and the k x k matrix `A` expanded from `a_seed`
+#[derive(Clone, Debug)]
+pub struct ModuleLWEKey {
+    pub a_seed: [u8; 32],
+    pub t: Vec<RingElement>,
+}
+
+impl ModuleLWEKey {
+    /// Largest rank `matrix_a` can expand without reusing a domain
+    pub const MAX_RANK: usize = 16;
+
+    /// Module rank k
+    pub fn rank(&self) -> usize {
+        self.t.len()
+    }
+
+    /// Size in bytes of uncompressed key: seed plus k polynomials
+    pub fn size_bytes(&self) -> usize {
+        32 + self.t.len() * N * 2
+    }
+
+    /// Expand the k x k matrix `A` from `a_seed`. Entry (i, j) uses
+    /// `from_seed` domain `i << 4 | j`, so ranks up to `MAX_RANK` never
+    /// reuse a domain. This is this crate's expansion, not Kyber's
+    /// `SampleNTT`: for a key parsed with `from_kyber_pk` it is not the
+    /// `A` Kyber uses.
+    pub fn matrix_a(&self) -> Vec<Vec<RingElement>> {
+        Self::expand_a(&self.a_seed, self.rank())
+    }
+
+    pub(crate) fn expand_a(seed: &[u8; 32], k: usize) -> Vec<Vec<RingElement>> {
+        RingElement::fill_matrix_from_seed(seed, k, k)
+    }
+}
+
 /// Compressed public key using algebraic sketching
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct CompressedPK {
-    /// Seed to regenerate polynomial 'a'
-    pub seed: [u8; 32],
+    /// Seed to regenerate polynomial 'a', inline or by well-known id
+    pub seed: SeedRef,
+
+    /// `from_seed` domain byte used to expand the seed into 'a'
+    pub a_domain: u8,
+
+    /// Number of polynomials sketched: 1 for a `RingLWEKey`, k for a
+    /// `ModuleLWEKey`. Anchors and parity hold each component in turn.
+    pub rank: u8,
+
+    /// Keep every `decimation`-th coefficient as an anchor. 2 is the
+    /// lossless anchor + parity layout; larger values come from
+    /// `compress_with_decimation`, carry no parity and only decode
+    /// approximately through `decompress_decimated`.
+    pub decimation: u16,
     
-    /// Anchor coefficients (every 2nd coefficient of b)
+    /// Anchor coefficients (every 2nd coefficient of b, or all of b
+    /// when `has_parity` is false)
+    #[serde(with = "packed12")]
     pub anchor_coeffs: Vec<u16>,
     
     /// Checksum for verification (hash of original b)
     pub checksum: [u8; 8],
+
+    /// Algorithm behind `checksum`
+    pub checksum_kind: ChecksumKind,
     
     /// Parity coefficients for reconstruction
     /// Stores XOR-like algebraic checksums for recovery
+    #[serde(with = "packed12")]
     pub parity: Vec<u16>,
+
+    /// Whether the sketch uses parity at all. False means `parity` is
+    /// intentionally empty and the anchors carry every coefficient; an
+    /// empty `parity` with this set is a truncated sketch.
+    pub has_parity: bool,
+
+    /// How `parity` relates each odd coefficient to its anchor
+    pub parity_mode: ParityMode,
+
+    /// Detached signature over the sketch, carried but never produced or
+    /// checked by this crate (not covered by the checksum)
+    pub signature: Option<Vec<u8>>,
+
+    /// Transmission sequence number for ordered reassembly / dedup
+    /// (set by the sender, not covered by the checksum)
+    #[cfg(feature = "seq")]
+    pub seq: u32,
 }
 
 impl CompressedPK {
     /// Size in bytes of compressed key
     pub fn size_bytes(&self) -> usize {
-        32 + // seed
-        self.anchor_coeffs.len() * 2 +
+        self.seed.size_bytes() + // seed
+        1 + // a domain
+        1 + // rank
+        2 + // decimation
+        self.coeff_bytes() +
+        8 + // checksum
+        1 + // checksum kind
+        1 + // has parity
+        1 + // parity mode
+        1 + self.signature.as_ref().map_or(0, Vec::len) + // signature
+        Self::SEQ_BYTES
+    }
+
+    /// Bytes spent on `b` itself: anchors and parity at 12 bits each, as
+    /// they go on the wire
+    pub fn coeff_bytes(&self) -> usize {
+        packed12::packed_len(self.anchor_coeffs.len()) + packed12::packed_len(self.parity.len())
+    }
+
+    /// Heap memory held by the sketch's vectors, by capacity rather than
+    /// length, so over-allocation shows up (unlike `size_bytes`)
+    pub fn heap_bytes(&self) -> usize {
+        (self.anchor_coeffs.capacity() + self.parity.capacity()) * std::mem::size_of::<u16>() +
+        self.signature.as_ref().map_or(0, Vec::capacity)
+    }
+
+    /// Coefficients of the full key regenerated from the seed (all of
+    /// `a`, per polynomial) versus those transmitted for `b` (anchors
+    /// plus parity), showing where the savings actually come from
+    pub fn recovered_vs_transmitted(&self) -> (usize, usize) {
+        (self.rank as usize * N, self.anchor_coeffs.len() + self.parity.len())
+    }
+
+    /// Bucket by the length of the `to_bytes` encoding, header included,
+    /// for aggregating telemetry. Fails as `to_bytes` does.
+    pub fn size_class(&self) -> Result<SizeClass, ILCError> {
+        Ok(match crate::to_bytes(self)?.len() {
+            0..=127 => SizeClass::Tiny,
+            128..=383 => SizeClass::Small,
+            384..=767 => SizeClass::Medium,
+            _ => SizeClass::Large,
+        })
+    }
+
+    #[cfg(feature = "seq")]
+    const SEQ_BYTES: usize = 4;
+    #[cfg(not(feature = "seq"))]
+    const SEQ_BYTES: usize = 0;
+
+    /// Replace the inline seed with a short id the receiver resolves
+    /// from its own registry of well-known seeds
+    pub fn with_seed_id(mut self, id: u32) -> Self {
+        self.seed = SeedRef::Id(id);
+        self
+    }
+
+    /// Record that 'a' was expanded from the seed under `domain`
+    /// rather than the default 0
+    pub fn with_a_domain(mut self, domain: u8) -> Self {
+        self.a_domain = domain;
+        self
+    }
+
+    /// Bundle a signature with the sketch, replacing any previous one
+    pub fn attach_signature(&mut self, signature: Vec<u8>) {
+        self.signature = Some(signature);
+    }
+
+    /// Remove and return the bundled signature, e.g. to verify it over
+    /// the unsigned sketch
+    pub fn take_signature(&mut self) -> Option<Vec<u8>> {
+        self.signature.take()
+    }
+
+    /// Stable bytes for a detached signature to cover: every field that
+    /// affects decoding, in a fixed little-endian layout independent of
+    /// the wire format, but not the signature itself or `seq`. The layout
+    /// is a domain tag, then seed (tag byte + 32-byte seed or u32 id),
+    /// a domain, rank, decimation, checksum kind, checksum, has parity,
+    /// parity mode, and the anchors and parity as u32 count + u16 values.
+    pub fn canonical_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(64 + 2 * (self.anchor_coeffs.len() + self.parity.len()));
+        out.extend_from_slice(b"ILC-canonical-v1");
+        match self.seed {
+            SeedRef::Inline(seed) => {
+                out.push(0);
+                out.extend_from_slice(&seed);
+            }
+            SeedRef::Id(id) => {
+                out.push(1);
+                out.extend_from_slice(&id.to_le_bytes());
+            }
+        }
+        out.push(self.a_domain);
+        out.push(self.rank);
+        out.extend_from_slice(&self.decimation.to_le_bytes());
+        out.push(self.checksum_kind as u8);
+        out.extend_from_slice(&self.checksum);
+        out.push(self.has_parity as u8);
+        out.push(self.parity_mode as u8);
+        for coeffs in [&self.anchor_coeffs, &self.parity] {
+            out.extend_from_slice(&(coeffs.len() as u32).to_le_bytes());
+            for c in coeffs {
+                out.extend_from_slice(&c.to_le_bytes());
+            }
+        }
+        out
+    }
+
+    /// Tag the sketch with a transmission sequence number
+    #[cfg(feature = "seq")]
+    pub fn with_seq(mut self, seq: u32) -> Self {
+        self.seq = seq;
+        self
+    }
+}
+
+/// Serde adapter storing reduced coefficients at 12 bits each, two per
+/// three bytes, instead of as u16. Since q < 2^12 this is lossless;
+/// values >= q fail to serialize, and to deserialize, so a decoded sketch
+/// only ever holds canonical coefficients.
+mod packed12 {
+    use crate::ring::Q;
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    /// Bytes needed for `n` packed coefficients
+    pub fn packed_len(n: usize) -> usize {
+        (n * 12).div_ceil(8)
+    }
+
+    pub fn serialize<S: Serializer>(coeffs: &[u16], serializer: S) -> Result<S::Ok, S::Error> {
+        if let Some(c) = coeffs.iter().find(|&&c| c as u32 >= Q) {
+            return Err(ser::Error::custom(format!("coefficient {c} is not reduced mod q")));
+        }
+        let mut bytes = Vec::with_capacity(packed_len(coeffs.len()) + 1);
+        for pair in coeffs.chunks(2) {
+            let (x, y) = (pair[0], pair.get(1).copied().unwrap_or(0));
+            bytes.extend([x as u8, (x >> 8) as u8 | (y << 4) as u8, (y >> 4) as u8]);
+        }
+        // An odd count leaves an all-zero final byte
+        bytes.truncate(packed_len(coeffs.len()));
+        serializer.serialize_bytes(&bytes)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u16>, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        let n = bytes.len() * 8 / 12;
+        if packed_len(n) != bytes.len() {
+            return Err(de::Error::custom("truncated 12-bit coefficient packing"));
+        }
+        let mut coeffs = Vec::with_capacity(n + 1);
+        for b in bytes.chunks(3) {
+            let byte = |i: usize| b.get(i).copied().unwrap_or(0) as u16;
+            coeffs.push(byte(0) | (byte(1) & 0x0f) << 8);
+            coeffs.push(byte(1) >> 4 | byte(2) << 4);
+        }
+        coeffs.truncate(n);
+        if let Some(c) = coeffs.iter().find(|&&c| c as u32 >= Q) {
+            return Err(de::Error::custom(format!("coefficient {c} is not reduced mod q")));
+        }
+        Ok(coeffs)
+    }
+}
+
+/// Signed values zigzag-mapped (0, -1, 1, -2, ...) and bit-packed LSB
+/// first at the narrowest width that holds them all, so values near zero
+/// take few bits
+mod zigzag {
+    use super::ILCError;
+
+    /// Pack `values`, returning the width in bits and the packed bytes
+    pub fn pack(values: &[i16]) -> (u8, Vec<u8>) {
+        let zigzag: Vec<u32> = values.iter().map(|&p| ((p << 1) ^ (p >> 15)) as u16 as u32).collect();
+        let bits = zigzag.iter().map(|z| 32 - z.leading_zeros() as usize).max().unwrap_or(0);
+        
+        let mut out = Vec::with_capacity((zigzag.len() * bits).div_ceil(8));
+        let (mut acc, mut held) = (0u32, 0);
+        for z in zigzag {
+            acc |= z << held;
+            held += bits;
+            while held >= 8 {
+                out.push(acc as u8);
+                acc >>= 8;
+                held -= 8;
+            }
+        }
+        if held > 0 {
+            out.push(acc as u8);
+        }
+        (bits as u8, out)
+    }
+
+    /// Unpack `count` values of `bits` each. Fails with `InvalidInput` if
+    /// the width exceeds 12 bits or `bytes` is not exactly that long.
+    pub fn unpack(bytes: &[u8], bits: u8, count: usize) -> Result<Vec<i16>, ILCError> {
+        let bits = bits as usize;
+        if bits > 12 || bytes.len() != (count * bits).div_ceil(8) {
+            return Err(ILCError::InvalidInput);
+        }
+        
+        let mask = (1u32 << bits) - 1;
+        let (mut acc, mut held) = (0u32, 0);
+        let mut bytes = bytes.iter();
+        let mut values = Vec::with_capacity(count);
+        for _ in 0..count {
+            while held < bits {
+                acc |= (*bytes.next().unwrap_or(&0) as u32) << held;
+                held += 8;
+            }
+            let z = (acc & mask) as i16;
+            acc >>= bits;
+            held -= bits;
+            values.push((z >> 1) ^ -(z & 1));
+        }
+        Ok(values)
+    }
+}
+
+/// Several `b` polynomials over the same 'a', from `compress_correlated`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CorrelatedSketch {
+    /// Seed to regenerate the shared polynomial 'a'
+    pub seed: SeedRef,
+
+    /// `from_seed` domain byte used to expand the seed into 'a'
+    pub a_domain: u8,
+
+    /// The first `b` as is, every later one delta-coded against it
+    pub entries: Vec<CorrelatedEntry>,
+}
+
+impl CorrelatedEntry {
+    /// Pack the centered coefficients of `coded`
+    pub(crate) fn pack(coded: &RingElement, checksum: [u8; 8]) -> Self {
+        let (bits, coeffs) = zigzag::pack(&coded.to_centered());
+        CorrelatedEntry { bits, coeffs, checksum }
+    }
+
+    /// Unpack the coded polynomial. Fails with `InvalidInput` on a width
+    /// over 12 bits or a length that isn't N values at that width.
+    pub(crate) fn unpack(&self) -> Result<RingElement, ILCError> {
+        let centered: [i16; N] = zigzag::unpack(&self.coeffs, self.bits, N)?
+            .try_into()
+            .map_err(|_| ILCError::InvalidInput)?;
+        Ok(RingElement::from_centered(&centered))
+    }
+}
+
+impl CorrelatedSketch {
+    /// Size in bytes of the correlated sketch
+    pub fn size_bytes(&self) -> usize {
+        self.seed.size_bytes() +
+        1 + // a domain
+        self.entries
+            .iter()
+            .map(|e| 1 + e.coeffs.len() + 8)
+            .sum::<usize>()
+    }
+}
+
+/// One polynomial of a `CorrelatedSketch`: its centered coefficients
+/// (of `b` itself for the first entry, of the delta for the rest),
+/// zigzag-mapped and bit-packed at the narrowest width that holds them.
+/// A full `b` takes 12 bits per coefficient; a delta of small noise far
+/// fewer.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CorrelatedEntry {
+    /// Bits per coefficient, at most 12
+    pub bits: u8,
+    pub coeffs: Vec<u8>,
+    /// Checksum of the decoded `b`, not of the delta
+    pub checksum: [u8; 8],
+}
+
+/// Per-compression figures reported by `RingLWEKey::compress_with_metrics`
+#[derive(Clone, Copy, Debug)]
+pub struct CompressionMetrics {
+    /// Wire size over uncompressed key size; `ratio_permille` without the
+    /// `float` feature
+    #[cfg(feature = "float")]
+    pub ratio: f64,
+    /// Length of the `to_bytes` encoding
+    pub wire_bytes: usize,
+    /// Size of the uncompressed key
+    pub key_bytes: usize,
+    /// Time spent hashing `b` for the checksum
+    pub checksum_time: Duration,
+}
+
+impl CompressionMetrics {
+    /// Wire size over uncompressed key size in parts per thousand,
+    /// rounded to nearest, using integer math only
+    pub fn ratio_permille(&self) -> u32 {
+        ((self.wire_bytes * 1000 + self.key_bytes / 2) / self.key_bytes) as u32
+    }
+}
+
+/// Update from one `b` to the next, from `RingLWEKey::compress_delta_from`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct CompressedDelta {
+    pub seed: SeedRef,
+    pub a_domain: u8,
+    /// `(index, new - old mod q)` for each coefficient that changed
+    pub changes: Vec<(u16, u16)>,
+    /// Checksum of the new `b`
+    pub checksum: [u8; 8],
+}
+
+impl CompressedDelta {
+    /// Size in bytes of the delta
+    pub fn size_bytes(&self) -> usize {
+        self.seed.size_bytes() +
+        1 + // a domain
+        self.changes.len() * 4 +
+        8 // checksum
+    }
+}
+
+/// Sketch from `RingLWEKey::compress_algebraic`. Only the even
+/// coefficients of `b` travel; the odd ones are rebuilt from
+/// `b = a*s + e` using the secret `s` and the odd error terms, both packed
+/// as signed 4-bit values.
+///
+/// **Warning:** the sketch contains the secret key `s` in the clear. It is
+/// not a public key encoding; only hand it to receivers entitled to `s`.
+/// Counting the secret it also saves nothing over 12-bit packing of `b`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AlgebraicPK {
+    pub seed: SeedRef,
+    pub a_domain: u8,
+    /// Even coefficients of b
+    #[serde(with = "packed12")]
+    pub anchor_coeffs: Vec<u16>,
+    /// Centered `s`, two coefficients per byte, low nibble first
+    pub secret: Vec<u8>,
+    /// Centered `e` at the odd indices, packed like `secret`
+    pub odd_errors: Vec<u8>,
+    pub checksum: [u8; 8],
+}
+
+impl AlgebraicPK {
+    /// Size in bytes of the sketch
+    pub fn size_bytes(&self) -> usize {
+        self.seed.size_bytes() +
+        1 + // a domain
+        self.coeff_bytes() +
+        8 // checksum
+    }
+
+    /// Bytes spent rebuilding `b`: packed anchors, odd errors and the
+    /// secret they depend on
+    pub fn coeff_bytes(&self) -> usize {
+        packed12::packed_len(self.anchor_coeffs.len()) + self.odd_errors.len() + self.secret.len()
+    }
+}
+
+/// Sketch from `RingLWEKey::compress_with_secret`. No coefficient of `b`
+/// travels at all: the receiver already holds `s` and rebuilds
+/// `b = a*s + e` from the seed and the packed error alone.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SecretErrorPK {
+    pub seed: SeedRef,
+    pub a_domain: u8,
+    /// Centered `e`, two coefficients per byte, low nibble first
+    pub errors: Vec<u8>,
+    pub checksum: [u8; 8],
+}
+
+impl SecretErrorPK {
+    /// Size in bytes of the sketch
+    pub fn size_bytes(&self) -> usize {
+        self.seed.size_bytes() +
+        1 + // a domain
+        self.coeff_bytes() +
+        8 // checksum
+    }
+
+    /// Bytes spent on `b` itself: the packed error
+    pub fn coeff_bytes(&self) -> usize {
+        self.errors.len()
+    }
+}
+
+/// Sketch with an arbitrary anchor pattern, from
+/// `RingLWEKey::compress_with_pattern`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PatternedPK {
+    pub seed: SeedRef,
+    pub a_domain: u8,
+    /// Bit `i` (LSB first) set when coefficient `i` is an anchor
+    pub keep_mask: [u8; N / 8],
+    /// Anchor coefficients in index order
+    #[serde(with = "packed12")]
+    pub anchor_coeffs: Vec<u16>,
+    /// One entry per non-anchor coefficient, relating it to the
+    /// coefficient just before it
+    #[serde(with = "packed12")]
+    pub parity: Vec<u16>,
+    pub checksum: [u8; 8],
+    pub parity_mode: ParityMode,
+}
+
+impl PatternedPK {
+    /// Whether coefficient `i` is an anchor
+    pub fn is_anchor(&self, i: usize) -> bool {
+        self.keep_mask[i / 8] >> (i % 8) & 1 == 1
+    }
+}
+
+/// `CompressedPK` with each anchor stored next to its parity, so the
+/// decompress loop reads one pair from one place instead of two vectors.
+/// In-memory only: send `to_separate()` through `to_bytes`, which packs
+/// coefficients at 12 bits.
+#[derive(Clone, Debug)]
+pub struct InterleavedPK {
+    pub seed: SeedRef,
+    pub a_domain: u8,
+    /// `(anchor, parity)` per coefficient pair
+    pub pairs: Vec<(u16, u16)>,
+    pub checksum: [u8; 8],
+    pub parity_mode: ParityMode,
+    pub signature: Option<Vec<u8>>,
+    #[cfg(feature = "seq")]
+    pub seq: u32,
+}
+
+impl InterleavedPK {
+    /// Convert to the separate-vector form
+    pub fn to_separate(&self) -> CompressedPK {
+        CompressedPK {
+            seed: self.seed,
+            a_domain: self.a_domain,
+            rank: 1,
+            decimation: 2,
+            anchor_coeffs: self.pairs.iter().map(|&(a, _)| a).collect(),
+            checksum: self.checksum,
+            checksum_kind: ChecksumKind::Sha3,
+            parity: self.pairs.iter().map(|&(_, p)| p).collect(),
+            has_parity: true,
+            parity_mode: self.parity_mode,
+            signature: self.signature.clone(),
+            #[cfg(feature = "seq")]
+            seq: self.seq,
+        }
+    }
+}
+
+impl CompressedPK {
+    /// Convert to the interleaved form. Fails with `InvalidInput` for
+    /// sketches without parity, of more than one polynomial, with a
+    /// non-SHA3 checksum, or with mismatched vector lengths.
+    pub fn to_interleaved(&self) -> Result<InterleavedPK, ILCError> {
+        if !self.has_parity || self.rank != 1 || self.decimation != 2 || self.checksum_kind != ChecksumKind::Sha3
+            || self.anchor_coeffs.len() != self.parity.len()
+        {
+            return Err(ILCError::InvalidInput);
+        }
+        Ok(InterleavedPK {
+            seed: self.seed,
+            a_domain: self.a_domain,
+            pairs: self.anchor_coeffs.iter().copied().zip(self.parity.iter().copied()).collect(),
+            checksum: self.checksum,
+            parity_mode: self.parity_mode,
+            signature: self.signature.clone(),
+            #[cfg(feature = "seq")]
+            seq: self.seq,
+        })
+    }
+}
+
+/// `CompressedPK` with parity stored balanced, in (-q/2, q/2] rather
+/// than [0, q). Sums that land just below q become small negatives,
+/// which suits a downstream entropy coder. In-memory only, like
+/// `InterleavedPK`: `to_standard()` is the wire form.
+#[derive(Clone, Debug)]
+pub struct BalancedPK {
+    pub seed: SeedRef,
+    pub a_domain: u8,
+    pub anchor_coeffs: Vec<u16>,
+    pub checksum: [u8; 8],
+    /// Centered parity per coefficient pair
+    pub parity: Vec<i16>,
+    pub parity_mode: ParityMode,
+    pub signature: Option<Vec<u8>>,
+    #[cfg(feature = "seq")]
+    pub seq: u32,
+}
+
+impl BalancedPK {
+    /// Convert back to `CompressedPK`, lifting each parity value into
+    /// [0, q)
+    pub fn to_standard(&self) -> CompressedPK {
+        CompressedPK {
+            seed: self.seed,
+            a_domain: self.a_domain,
+            rank: 1,
+            decimation: 2,
+            anchor_coeffs: self.anchor_coeffs.clone(),
+            checksum: self.checksum,
+            checksum_kind: ChecksumKind::Sha3,
+            // 10q > 2^15 lifts every i16 to a non-negative value
+            parity: self.parity.iter().map(|&p| barrett_reduce((p as i32 + 10 * Q as i32) as u32)).collect(),
+            has_parity: true,
+            parity_mode: self.parity_mode,
+            signature: self.signature.clone(),
+            #[cfg(feature = "seq")]
+            seq: self.seq,
+        }
+    }
+}
+
+impl CompressedPK {
+    /// Convert to the balanced-parity form. Fails with `InvalidInput` for
+    /// sketches without parity, of more than one polynomial, decimated,
+    /// or with a non-SHA3 checksum.
+    pub fn to_balanced(&self) -> Result<BalancedPK, ILCError> {
+        if !self.has_parity || self.rank != 1 || self.decimation != 2 || self.checksum_kind != ChecksumKind::Sha3 {
+            return Err(ILCError::InvalidInput);
+        }
+        let center = |p: u16| {
+            let p = barrett_reduce(p as u32) as u32;
+            if p > Q / 2 { p as i16 - Q as i16 } else { p as i16 }
+        };
+        Ok(BalancedPK {
+            seed: self.seed,
+            a_domain: self.a_domain,
+            anchor_coeffs: self.anchor_coeffs.clone(),
+            checksum: self.checksum,
+            parity: self.parity.iter().map(|&p| center(p)).collect(),
+            parity_mode: self.parity_mode,
+            signature: self.signature.clone(),
+            #[cfg(feature = "seq")]
+            seq: self.seq,
+        })
+    }
+}
+
+/// `BalancedPK` with each centered parity value zigzag-mapped (0, -1, 1,
+/// -2, ...) and bit-packed at the narrowest width that holds them all, from
+/// `RingLWEKey::compress_parity_opt`. Parity near zero, as from smooth
+/// keys under `ParityMode::Difference`, packs below 12 bits; uniform
+/// parity falls back to 12 bits, the same as `CompressedPK`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NarrowParityPK {
+    pub seed: SeedRef,
+    pub a_domain: u8,
+    #[serde(with = "packed12")]
+    pub anchor_coeffs: Vec<u16>,
+    pub checksum: [u8; 8],
+    /// Bits per parity value, at most 12
+    pub parity_bits: u8,
+    pub parity: Vec<u8>,
+    pub parity_mode: ParityMode,
+    pub signature: Option<Vec<u8>>,
+    #[cfg(feature = "seq")]
+    pub seq: u32,
+}
+
+impl NarrowParityPK {
+    /// Size in bytes of the sketch
+    pub fn size_bytes(&self) -> usize {
+        self.seed.size_bytes() +
+        1 + // a domain
+        self.coeff_bytes() +
+        8 + // checksum
+        1 + // parity bits
+        1 // parity mode
+    }
+
+    /// Bytes spent on `b` itself: packed anchors and narrow parity
+    pub fn coeff_bytes(&self) -> usize {
+        packed12::packed_len(self.anchor_coeffs.len()) + self.parity.len()
+    }
+
+    /// Unpack into the balanced form. Fails with `InvalidInput` if the
+    /// width exceeds 12 bits or the parity doesn't hold one value per
+    /// anchor.
+    pub fn to_balanced(&self) -> Result<BalancedPK, ILCError> {
+        let parity = zigzag::unpack(&self.parity, self.parity_bits, self.anchor_coeffs.len())?;
+        Ok(BalancedPK {
+            seed: self.seed,
+            a_domain: self.a_domain,
+            anchor_coeffs: self.anchor_coeffs.clone(),
+            checksum: self.checksum,
+            parity,
+            parity_mode: self.parity_mode,
+            signature: self.signature.clone(),
+            #[cfg(feature = "seq")]
+            seq: self.seq,
+        })
+    }
+}
+
+impl BalancedPK {
+    /// Zigzag and bit-pack the parity at the narrowest width that fits
+    pub fn to_narrow(&self) -> NarrowParityPK {
+        let (bits, parity) = zigzag::pack(&self.parity);
+        NarrowParityPK {
+            seed: self.seed,
+            a_domain: self.a_domain,
+            anchor_coeffs: self.anchor_coeffs.clone(),
+            checksum: self.checksum,
+            parity_bits: bits,
+            parity,
+            parity_mode: self.parity_mode,
+            signature: self.signature.clone(),
+            #[cfg(feature = "seq")]
+            seq: self.seq,
+        }
+    }
+}
+
+/// Binary patch from one `CompressedPK` to another, from
+/// `CompressedPK::diff`. Header fields are carried whole; anchors and
+/// parity only where they changed.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SketchPatch {
+    pub seed: SeedRef,
+    pub a_domain: u8,
+    pub rank: u8,
+    pub decimation: u16,
+    /// Lengths of the target's anchor and parity vectors
+    pub anchor_len: u16,
+    pub parity_len: u16,
+    /// `(index, new value)` for each anchor that changed
+    pub anchor_changes: Vec<(u16, u16)>,
+    /// `(index, new value)` for each parity coefficient that changed
+    pub parity_changes: Vec<(u16, u16)>,
+    pub checksum: [u8; 8],
+    pub checksum_kind: ChecksumKind,
+    pub has_parity: bool,
+    pub parity_mode: ParityMode,
+    pub signature: Option<Vec<u8>>,
+    #[cfg(feature = "seq")]
+    pub seq: u32,
+}
+
+impl SketchPatch {
+    /// Size in bytes of the patch
+    pub fn size_bytes(&self) -> usize {
+        self.seed.size_bytes() +
+        1 + // a domain
+        1 + // rank
+        2 + // decimation
+        4 + // lengths
+        (self.anchor_changes.len() + self.parity_changes.len()) * 4 +
         8 + // checksum
-        self.parity.len() * 2
+        1 + // checksum kind
+        1 + // has parity
+        1 + // parity mode
+        1 + self.signature.as_ref().map_or(0, Vec::len) + // signature
+        CompressedPK::SEQ_BYTES
+    }
+}
+
+/// `(index, new value)` wherever `new` differs from `old`, with `old`
+/// read as zero past its end
+fn vec_changes(old: &[u16], new: &[u16]) -> Vec<(u16, u16)> {
+    new.iter()
+        .enumerate()
+        .filter(|&(i, &v)| old.get(i).copied().unwrap_or(0) != v)
+        .map(|(i, &v)| (i as u16, v))
+        .collect()
+}
+
+/// Inverse of `vec_changes`: resize `old` to `len` and overwrite the
+/// changed entries
+fn apply_changes(old: &[u16], len: u16, changes: &[(u16, u16)]) -> Result<Vec<u16>, ILCError> {
+    let mut out = old.to_vec();
+    out.resize(len as usize, 0);
+    for &(i, v) in changes {
+        *out.get_mut(i as usize).ok_or(ILCError::InvalidInput)? = v;
+    }
+    Ok(out)
+}
+
+impl CompressedPK {
+    /// Patch turning `self` into `other`, for updating a provisioned key
+    /// without resending the whole sketch
+    pub fn diff(&self, other: &CompressedPK) -> SketchPatch {
+        SketchPatch {
+            seed: other.seed,
+            a_domain: other.a_domain,
+            rank: other.rank,
+            decimation: other.decimation,
+            anchor_len: other.anchor_coeffs.len() as u16,
+            parity_len: other.parity.len() as u16,
+            anchor_changes: vec_changes(&self.anchor_coeffs, &other.anchor_coeffs),
+            parity_changes: vec_changes(&self.parity, &other.parity),
+            checksum: other.checksum,
+            checksum_kind: other.checksum_kind,
+            has_parity: other.has_parity,
+            parity_mode: other.parity_mode,
+            signature: other.signature.clone(),
+            #[cfg(feature = "seq")]
+            seq: other.seq,
+        }
+    }
+
+    /// Apply a patch from `diff`. Fails with `InvalidInput` if a change
+    /// indexes past the target length; whether the patch was made against
+    /// this base is only caught by the checksum on decompression.
+    pub fn apply_patch(&self, patch: &SketchPatch) -> Result<CompressedPK, ILCError> {
+        Ok(CompressedPK {
+            seed: patch.seed,
+            a_domain: patch.a_domain,
+            rank: patch.rank,
+            decimation: patch.decimation,
+            anchor_coeffs: apply_changes(&self.anchor_coeffs, patch.anchor_len, &patch.anchor_changes)?,
+            checksum: patch.checksum,
+            checksum_kind: patch.checksum_kind,
+            parity: apply_changes(&self.parity, patch.parity_len, &patch.parity_changes)?,
+            has_parity: patch.has_parity,
+            parity_mode: patch.parity_mode,
+            signature: patch.signature.clone(),
+            #[cfg(feature = "seq")]
+            seq: patch.seq,
+        })
+    }
+}
+
+/// Where the receiver gets the seed for 'a'
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedRef {
+    /// Seed carried in the sketch
+    Inline([u8; 32]),
+    /// Well-known public seed identified by a short id
+    Id(u32),
+}
+
+impl SeedRef {
+    /// Payload size on the wire
+    pub fn size_bytes(&self) -> usize {
+        match self {
+            SeedRef::Inline(_) => 32,
+            SeedRef::Id(_) => 4,
+        }
+    }
+
+    /// Seed bytes, looking ids up in `registry`
+    pub fn resolve(&self, registry: impl Fn(u32) -> Option<[u8; 32]>) -> Result<[u8; 32], ILCError> {
+        match *self {
+            SeedRef::Inline(seed) => Ok(seed),
+            SeedRef::Id(id) => registry(id).ok_or(ILCError::UnknownSeedId(id)),
+        }
     }
 }
 
+/// Algorithm behind `CompressedPK::checksum`, chosen with
+/// `RingLWEKey::compress_with_checksum`. All fill the same 8 bytes.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ChecksumKind {
+    /// SHA3-256 over the coefficients, truncated to 8 bytes
+    #[default]
+    Sha3,
+    /// CRC-32 (IEEE) over the coefficients in the first 4 bytes, the rest
+    /// zero. Much cheaper and catches transmission errors, but offers no
+    /// protection against deliberate tampering.
+    Crc32,
+    /// No check at all: all-zero, and every reconstruction is accepted
+    None,
+    /// Keyed HMAC-SHA3-256 tag from `compress_with_mac`; only
+    /// `decompress_verify_mac` can check it
+    Mac,
+    /// Hash chained onto the previous sketch, from `compress_chained`;
+    /// only `decompress_chained` and `verify_chain` can check it
+    Chained,
+}
+
+/// Relation stored in the parity vector for each (even, odd) pair
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ParityMode {
+    /// `parity = even + odd mod q`
+    #[default]
+    Sum,
+    /// `parity = odd - even mod q`. Same size on the wire, but small for
+    /// structured keys whose neighbouring coefficients are correlated,
+    /// which helps a downstream delta/entropy coder.
+    Difference,
+}
+
+/// Coarse wire size bucket of a sketch, from `CompressedPK::size_class`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SizeClass {
+    /// Under 128 bytes
+    Tiny,
+    /// 128 to 383 bytes
+    Small,
+    /// 384 to 767 bytes; Kyber-512 sketches with or without parity
+    Medium,
+    /// 768 bytes and up, e.g. with a large signature attached
+    Large,
+}
+
+/// Decimation preset, from most to least faithful; see
+/// `RingLWEKey::compress_with_decimation` and `sketcher::autotune`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CompressionLevel {
+    /// Stride 2 with parity: exact
+    #[default]
+    Lossless,
+    /// Every 4th coefficient, the rest interpolated
+    Decimate4,
+    /// Every 8th coefficient
+    Decimate8,
+    /// Every 16th coefficient
+    Decimate16,
+}
+
+impl CompressionLevel {
+    /// All levels, most faithful first
+    pub const ALL: [CompressionLevel; 4] = [
+        CompressionLevel::Lossless,
+        CompressionLevel::Decimate4,
+        CompressionLevel::Decimate8,
+        CompressionLevel::Decimate16,
+    ];
+
+    /// Anchor stride for `compress_with_decimation`
+    pub fn decimation(self) -> usize {
+        match self {
+            CompressionLevel::Lossless => 2,
+            CompressionLevel::Decimate4 => 4,
+            CompressionLevel::Decimate8 => 8,
+            CompressionLevel::Decimate16 => 16,
+        }
+    }
+}
+
+/// Decoder tried by `RingLWEKey::decompress_with_fallback`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StrategyId {
+    /// Plain `decompress`, trusting the recorded `parity_mode`
+    Standard,
+    /// Read the parity as `ParityMode::Sum`, whatever is recorded
+    SumParity,
+    /// Read the parity as `ParityMode::Difference`, whatever is recorded
+    DifferenceParity,
+    /// `decompress_repair`, correcting one flipped anchor bit
+    Repair,
+}
+
 /// Error type for compression/decompression
 #[derive(Debug, Clone)]
 pub enum ILCError {
     ReconstructionFailed,
     ChecksumMismatch,
     InvalidInput,
+    UnknownSeedId(u32),
+    BadMagic,
+    /// Encoding failed, with the encoder's message
+    Serialization(String),
+    /// Decoding failed; the decoder's error is kept as `source()`
+    Deserialization(Arc<dyn std::error::Error + Send + Sync>),
+    /// The wire header names a format version this build can't decode
+    UnsupportedVersion(u8),
+    /// The wire header's layout flags differ from this build's `WIRE_FLAGS`
+    UnsupportedFlags(u8),
+    /// An accumulator overflowed in `try_mul`
+    Overflow,
 }
 
 impl std::fmt::Display for ILCError {
@@ -59,14 +980,44 @@ impl std::fmt::Display for ILCError {
             ILCError::ReconstructionFailed => write!(f, "Failed to reconstruct key"),
             ILCError::ChecksumMismatch => write!(f, "Checksum verification failed"),
             ILCError::InvalidInput => write!(f, "Invalid input data"),
+            ILCError::UnknownSeedId(id) => write!(f, "Unknown seed id {}", id),
+            ILCError::BadMagic => write!(f, "Missing ILC magic prefix"),
+            ILCError::Serialization(msg) => write!(f, "Serialization failed: {}", msg),
+            ILCError::Deserialization(cause) => write!(f, "Deserialization failed: {}", cause),
+            ILCError::UnsupportedVersion(v) => write!(f, "Unsupported wire format version {}", v),
+            ILCError::UnsupportedFlags(flags) => write!(f, "Unsupported wire format flags {:#04x}", flags),
+            ILCError::Overflow => write!(f, "Arithmetic overflow"),
         }
     }
 }
 
-impl std::error::Error for ILCError {}
+impl std::error::Error for ILCError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ILCError::Deserialization(cause) => Some(cause.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 /// Trait for algebraic compression
 pub trait AlgebraicShield {
     fn compress(&self, seed: [u8; 32]) -> CompressedPK;
     fn decompress(sketch: &CompressedPK) -> Result<Self, ILCError> where Self: Sized;
 }
+
+/// Object-safe compression strategy, so heterogeneous strategies can sit
+/// behind `Box<dyn DynStrategy>`. Implemented for `ParityMode` and for
+/// closures with the same signature.
+pub trait DynStrategy {
+    fn compress_dyn(&self, key: &RingLWEKey, seed: [u8; 32]) -> Result<CompressedPK, ILCError>;
+}
+
+impl<F> DynStrategy for F
+where
+    F: Fn(&RingLWEKey, [u8; 32]) -> Result<CompressedPK, ILCError>,
+{
+    fn compress_dyn(&self, key: &RingLWEKey, seed: [u8; 32]) -> Result<CompressedPK, ILCError> {
+        self(key, seed)
+    }
+}
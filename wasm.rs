@@ -0,0 +1,54 @@
+//! WebAssembly bindings for browser-side compression and decompression
+//!
+//! Enabled by the `wasm` feature. Sketches cross the boundary in the
+//! `to_bytes` wire format; every `ILCError`, including malformed input
+//! from JS, comes back as a `JsValue` message rather than a panic.
+
+use crate::ring::{N, Q};
+use crate::types::ILCError;
+use wasm_bindgen::prelude::*;
+
+fn to_js(err: ILCError) -> JsValue {
+    JsValue::from_str(&err.to_string())
+}
+
+/// Compress `b` (N coefficients, each < q) under a 32-byte seed into
+/// the wire format
+#[wasm_bindgen]
+pub fn compress_js(b_coeffs: &[u16], seed: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let b: [u16; N] = b_coeffs.try_into().map_err(|_| to_js(ILCError::InvalidInput))?;
+    if b.iter().any(|&c| c as u32 >= Q) {
+        return Err(to_js(ILCError::InvalidInput));
+    }
+    let seed: [u8; 32] = seed.try_into().map_err(|_| to_js(ILCError::InvalidInput))?;
+    crate::to_bytes(&crate::compress(&b, seed)).map_err(to_js)
+}
+
+/// Decode a wire-format sketch and recover the coefficients of `b`
+#[wasm_bindgen]
+pub fn decompress_js(bytes: &[u8]) -> Result<Vec<u16>, JsValue> {
+    let sketch = crate::from_bytes(bytes).map_err(to_js)?;
+    Ok(crate::decompress(&sketch).map_err(to_js)?.to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ring::RingElement;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn test_js_roundtrip() {
+        let seed = [5u8; 32];
+        let b = RingElement::from_seed(&seed, 1).coeffs;
+
+        let bytes = compress_js(&b, &seed).unwrap();
+        assert_eq!(decompress_js(&bytes).unwrap(), b);
+
+        // Wrong lengths and out-of-range coefficients are errors, not panics
+        assert!(compress_js(&b[1..], &seed).is_err());
+        assert!(compress_js(&b, &seed[1..]).is_err());
+        assert!(compress_js(&[Q as u16; N], &seed).is_err());
+        assert!(decompress_js(&bytes[..bytes.len() - 1]).is_err());
+    }
+}